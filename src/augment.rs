@@ -0,0 +1,169 @@
+use ndarray::{Array1, Array2};
+use ndarray_rand::rand::Rng;
+use ndarray_rand::rand_distr::{Distribution, Normal, Uniform};
+
+/**Random changes made to the training images, so that every epoch sees fresh variants of them.
+ * Each setting gives the largest change in either direction, and 0 turns it off.
+ * Images are square rows of pixel values between 0 and 1 (eg. 784 values for 28x28). */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Augmentation {
+    //largest rotation, in degrees
+    pub rotation: f64,
+    //largest shift horizontally and vertically, in pixels
+    pub shift: f64,
+    //largest change in size, as a fraction (eg. 0.1 for 90% to 110%)
+    pub scale: f64,
+    //largest horizontal shear, as a fraction of the height
+    pub shear: f64,
+    //strength of elastic distortions, which scales the smoothed random displacements
+    pub elastic_alpha: f64,
+    //smoothness of elastic distortions, the standard deviation of their Gaussian blur
+    pub elastic_sigma: f64,
+    //standard deviation of the Gaussian noise added to each pixel
+    pub noise: f64,
+    //seed for the random changes, the same seed producing the same variants
+    pub seed: u64,
+}
+
+impl Default for Augmentation {
+    /**No changes at all */
+    fn default() -> Self {
+        Self {
+            rotation: 0.0,
+            shift: 0.0,
+            scale: 0.0,
+            shear: 0.0,
+            elastic_alpha: 0.0,
+            elastic_sigma: 4.0,
+            noise: 0.0,
+            seed: 0,
+        }
+    }
+}
+
+impl Augmentation {
+    /**Small changes suited to handwritten digits, which keep them recognizable */
+    pub fn digits(seed: u64) -> Self {
+        Self {
+            rotation: 10.0,
+            shift: 2.0,
+            scale: 0.1,
+            shear: 0.15,
+            elastic_alpha: 8.0,
+            elastic_sigma: 4.0,
+            noise: 0.02,
+            seed,
+        }
+    }
+
+    /**Returns a randomly changed copy of an image */
+    pub fn apply(&self, image: &Array1<f64>, rng: &mut impl Rng) -> Array1<f64> {
+        let side = (image.len() as f64).sqrt() as usize;
+        assert_eq!(side * side, image.len(), "images must be square");
+        let img = image.to_shape((side, side)).unwrap().to_owned();
+        let mut out = self.affine(&img, rng);
+        if self.elastic_alpha > 0.0 {
+            out = self.elastic(&out, rng);
+        }
+        if self.noise > 0.0 {
+            let normal = Normal::new(0.0, self.noise).unwrap();
+            out.mapv_inplace(|v| (v + normal.sample(rng)).clamp(0.0, 1.0));
+        }
+        Array1::from_iter(out)
+    }
+
+    /*Rotates, shifts, scales and shears an image about its center*/
+    fn affine(&self, img: &Array2<f64>, rng: &mut impl Rng) -> Array2<f64> {
+        let mut uniform = |range: f64| {
+            if range > 0.0 {
+                rng.sample(Uniform::new_inclusive(-range, range))
+            } else {
+                0.0
+            }
+        };
+        let angle = uniform(self.rotation).to_radians();
+        let scale = 1.0 + uniform(self.scale);
+        let shear = uniform(self.shear);
+        let (dx, dy) = (uniform(self.shift), uniform(self.shift));
+        //the forward transform is rotation * shear * scale, [[a, b], [c, d]]
+        let (sin, cos) = angle.sin_cos();
+        let (a, b) = (cos * scale, (cos * shear - sin) * scale);
+        let (c, d) = (sin * scale, (sin * shear + cos) * scale);
+        //each output pixel is looked up in the image through the inverse transform
+        let det = a * d - b * c;
+        let center = (img.dim().0 as f64 - 1.0) / 2.0;
+        Array2::from_shape_fn(img.dim(), |(row, col)| {
+            let x = col as f64 - center - dx;
+            let y = row as f64 - center - dy;
+            let src_x = (d * x - b * y) / det + center;
+            let src_y = (-c * x + a * y) / det + center;
+            bilinear(img, src_y, src_x)
+        })
+    }
+
+    /*Moves every pixel by a smooth random displacement field (Simard et al., 2003)*/
+    fn elastic(&self, img: &Array2<f64>, rng: &mut impl Rng) -> Array2<f64> {
+        let dist = Uniform::new_inclusive(-1.0, 1.0);
+        let mut field = || {
+            let raw = Array2::from_shape_fn(img.dim(), |_| rng.sample(dist));
+            let smooth = if self.elastic_sigma > 0.0 {
+                gaussian_blur(&raw, self.elastic_sigma)
+            } else {
+                raw
+            };
+            smooth * self.elastic_alpha
+        };
+        let (dx, dy) = (field(), field());
+        Array2::from_shape_fn(img.dim(), |(row, col)| {
+            bilinear(
+                img,
+                row as f64 + dy[[row, col]],
+                col as f64 + dx[[row, col]],
+            )
+        })
+    }
+}
+
+/*Samples an image between pixels, treating everything outside it as background*/
+fn bilinear(img: &Array2<f64>, y: f64, x: f64) -> f64 {
+    let (y0, x0) = (y.floor(), x.floor());
+    let (fy, fx) = (y - y0, x - x0);
+    let pixel = |r: f64, c: f64| {
+        if r < 0.0 || c < 0.0 || r >= img.dim().0 as f64 || c >= img.dim().1 as f64 {
+            0.0
+        } else {
+            img[[r as usize, c as usize]]
+        }
+    };
+    pixel(y0, x0) * (1.0 - fy) * (1.0 - fx)
+        + pixel(y0, x0 + 1.0) * (1.0 - fy) * fx
+        + pixel(y0 + 1.0, x0) * fy * (1.0 - fx)
+        + pixel(y0 + 1.0, x0 + 1.0) * fy * fx
+}
+
+/*Blurs an image with a Gaussian kernel, one axis at a time*/
+fn gaussian_blur(img: &Array2<f64>, sigma: f64) -> Array2<f64> {
+    let radius = (3.0 * sigma).ceil() as isize;
+    let kernel: Vec<f64> = (-radius..=radius)
+        .map(|i| (-((i * i) as f64) / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let total: f64 = kernel.iter().sum();
+    let (rows, cols) = (img.dim().0 as isize, img.dim().1 as isize);
+    //positions past the edges reuse the nearest pixel
+    let blur = |src: &Array2<f64>, along_rows: bool| {
+        Array2::from_shape_fn(src.dim(), |(r, c)| {
+            let mut sum = 0.0;
+            for (k, w) in kernel.iter().enumerate() {
+                let offset = k as isize - radius;
+                let (rr, cc) = if along_rows {
+                    ((r as isize + offset).clamp(0, rows - 1), c as isize)
+                } else {
+                    (r as isize, (c as isize + offset).clamp(0, cols - 1))
+                };
+                sum += w * src[[rr as usize, cc as usize]];
+            }
+            sum / total
+        })
+    };
+    blur(&blur(img, false), true)
+}
@@ -0,0 +1,154 @@
+use memmap2::Mmap;
+use ndarray::{s, Array, Array2, ArrayView};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::read_data::{DataError, DataSource};
+
+/*The cache starts with a fixed size header:
+ * magic (8 bytes), version (4), columns per image (4), number of images (8),
+ * fingerprint of the source files (8) and checksum of the body (8), all little-endian.
+ * The body holds every label as a byte, followed by every pixel as a byte.*/
+const CACHE_MAGIC: &[u8; 8] = b"NNCACHE\0";
+//bumped whenever the layout changes so that old caches are rebuilt
+const CACHE_VERSION: u32 = 1;
+const HEADER_LEN: usize = 40;
+//extension added to the name of the source file
+const CACHE_EXTENSION: &str = "cache";
+//starting value of the FNV-1a hash
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/**Reads a split of the data set like DataSource::read, through a binary cache stored next to
+ * the source files. The cache is written on the first load and memory mapped on later ones,
+ * and is rebuilt automatically when a source file's size or modification time changes.
+ * Failing to write the cache (eg. in a read-only directory) only means it is skipped. */
+pub fn read_cached(source: &DataSource) -> Result<Array2<u8>, DataError> {
+    let cache_path = cache_path(source);
+    let fingerprint = fingerprint(source)?;
+    if let Some(data) = read_cache(&cache_path, fingerprint) {
+        return Ok(data);
+    }
+    let data = source.read()?;
+    //a failed write leaves no cache behind, so the source is simply read again next time
+    if write_cache(&cache_path, fingerprint, &data).is_err() {
+        let _ = fs::remove_file(temp_path(&cache_path));
+    }
+    Ok(data)
+}
+
+/**Returns where the cache of a split is stored */
+pub fn cache_path(source: &DataSource) -> PathBuf {
+    let path = match source {
+        DataSource::Csv(path) => path,
+        DataSource::Idx { images, .. } => images,
+    };
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(CACHE_EXTENSION);
+    PathBuf::from(name)
+}
+
+/*Where the cache is written before being moved into place,
+ * so that a half-written cache is never read*/
+fn temp_path(cache_path: &Path) -> PathBuf {
+    let mut name = cache_path.as_os_str().to_owned();
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+/*FNV-1a, a simple and fast hash used for the fingerprint and checksum*/
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(hash, |h, &b| (h ^ b as u64).wrapping_mul(0x100_0000_01b3))
+}
+
+/*Identifies the current contents of the source files by their sizes and modification times*/
+fn fingerprint(source: &DataSource) -> Result<u64, DataError> {
+    let files = match source {
+        DataSource::Csv(path) => vec![path],
+        DataSource::Idx { images, labels } => vec![images, labels],
+    };
+    let mut hash = FNV_OFFSET;
+    for path in files {
+        let meta = fs::metadata(path).map_err(|source| DataError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .unwrap_or_default();
+        hash = fnv1a(hash, &meta.len().to_le_bytes());
+        hash = fnv1a(hash, &modified.as_secs().to_le_bytes());
+        hash = fnv1a(hash, &modified.subsec_nanos().to_le_bytes());
+    }
+    Ok(hash)
+}
+
+/*Reads a little-endian integer out of the header*/
+fn header_u64(header: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(header[at..at + 8].try_into().unwrap())
+}
+fn header_u32(header: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(header[at..at + 4].try_into().unwrap())
+}
+
+/*Memory maps the cache and copies its body into the layout of DataSource::read,
+ * returning None if there is no usable cache for the current source files*/
+fn read_cache(path: &Path, fingerprint: u64) -> Option<Array2<u8>> {
+    let file = File::open(path).ok()?;
+    //Safety: the cache is only ever replaced by renaming a new file over it,
+    //so the mapped file is not modified while it is being read
+    let map = unsafe { Mmap::map(&file) }.ok()?;
+    if map.len() < HEADER_LEN || &map[..8] != CACHE_MAGIC {
+        return None;
+    }
+    let (header, body) = map.split_at(HEADER_LEN);
+    let cols = header_u32(header, 12) as usize;
+    let count = header_u64(header, 16) as usize;
+    if header_u32(header, 8) != CACHE_VERSION
+        || header_u64(header, 24) != fingerprint
+        || body.len() != count.checked_mul(cols + 1)?
+        || fnv1a(FNV_OFFSET, body) != header_u64(header, 32)
+    {
+        return None;
+    }
+    let (labels, pixels) = body.split_at(count);
+    let mut data = Array::zeros((count, cols + 1));
+    data.column_mut(0).assign(&ArrayView::from(labels));
+    data.slice_mut(s![.., 1..])
+        .assign(&ArrayView::from_shape((count, cols), pixels).ok()?);
+    Some(data)
+}
+
+/*Writes the cache*/
+fn write_cache(path: &Path, fingerprint: u64, data: &Array2<u8>) -> Result<(), DataError> {
+    let (count, cols) = (data.dim().0, data.dim().1.saturating_sub(1));
+    let mut body = Vec::with_capacity(count * (cols + 1));
+    body.extend(data.column(0).iter());
+    body.extend(data.slice(s![.., 1..]).iter());
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(CACHE_MAGIC);
+    header.extend_from_slice(&CACHE_VERSION.to_le_bytes());
+    header.extend_from_slice(&(cols as u32).to_le_bytes());
+    header.extend_from_slice(&(count as u64).to_le_bytes());
+    header.extend_from_slice(&fingerprint.to_le_bytes());
+    header.extend_from_slice(&fnv1a(FNV_OFFSET, &body).to_le_bytes());
+
+    let temp = temp_path(path);
+    let io = |source| DataError::Io {
+        path: temp.clone(),
+        source,
+    };
+    let mut file = File::create(&temp).map_err(io)?;
+    file.write_all(&header).map_err(io)?;
+    file.write_all(&body).map_err(io)?;
+    fs::rename(&temp, path).map_err(|source| DataError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
@@ -0,0 +1,227 @@
+use ndarray::{Array, Array1, Array2};
+use ndarray_rand::rand::rngs::StdRng;
+use ndarray_rand::rand::seq::SliceRandom;
+use ndarray_rand::rand::SeedableRng;
+
+use crate::read_data::{read_split, DataError, DataSource, DatasetKind};
+
+/**A trait describing a labelled classification data set, so that the networks
+ * can be sized and trained from any source of data rather than only MNIST */
+pub trait Dataset {
+    /**The number of samples in the data set */
+    fn len(&self) -> usize;
+    /**The number of values in each sample's input */
+    fn input_dim(&self) -> usize;
+    /**The number of classes the samples are labelled with */
+    fn num_classes(&self) -> usize;
+    /**Returns the input and class of the sample at index i */
+    fn get(&self, i: usize) -> (Array1<f64>, usize);
+
+    /**Returns the class of the sample at index i, without copying its input */
+    fn class_of(&self, i: usize) -> usize {
+        self.get(i).1
+    }
+
+    /**Returns a readable name for a class, by default its number */
+    fn class_name(&self, class: usize) -> String {
+        class.to_string()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /**Iterates over every sample in order */
+    fn iter(&self) -> impl Iterator<Item = (Array1<f64>, usize)> + '_
+    where
+        Self: Sized,
+    {
+        (0..self.len()).map(move |i| self.get(i))
+    }
+
+    /**Returns the data set as two arrays, x with one sample per row,
+     * and y with the one hot encoded class of each sample */
+    fn to_arrays(&self) -> (Array2<f64>, Array2<f64>) {
+        let mut x = Array::zeros((self.len(), self.input_dim()));
+        let mut y = Array::zeros((self.len(), self.num_classes()));
+        for i in 0..self.len() {
+            let (input, class) = self.get(i);
+            x.row_mut(i).assign(&input);
+            y[[i, class]] = 1.0;
+        }
+        (x, y)
+    }
+}
+
+/**The MNIST data set of 28x28 handwritten digits, or one of the data sets sharing its layout.
+ * The pixels are kept as bytes and only converted to values between 0 and 1
+ * when a sample is retrieved, taking an eighth of the memory of floats */
+pub struct MnistDataset {
+    kind: DatasetKind,
+    images: Array2<u8>,
+    labels: Array1<usize>,
+}
+
+impl MnistDataset {
+    /**Loads a split of the data set
+     * source - Where to read the split from
+     * kind - Which data set the split belongs to
+     * size - How many images to retrieve, or None for all of them
+     */
+    pub fn load(
+        source: &DataSource,
+        kind: DatasetKind,
+        size: Option<usize>,
+    ) -> Result<Self, DataError> {
+        let (images, labels) = read_split(source, kind, size)?;
+        Ok(Self {
+            kind,
+            images,
+            labels,
+        })
+    }
+
+    /**Returns the raw pixel values, one image per row */
+    pub fn pixels(&self) -> &Array2<u8> {
+        &self.images
+    }
+
+    /**Returns which data set the images belong to */
+    pub fn kind(&self) -> DatasetKind {
+        self.kind
+    }
+}
+
+impl Dataset for MnistDataset {
+    fn len(&self) -> usize {
+        self.labels.len()
+    }
+    fn input_dim(&self) -> usize {
+        self.images.dim().1
+    }
+    fn num_classes(&self) -> usize {
+        self.kind.num_classes()
+    }
+    fn class_name(&self, class: usize) -> String {
+        self.kind.class_names()[class].to_string()
+    }
+    fn get(&self, i: usize) -> (Array1<f64>, usize) {
+        //converting to 0 to 1 interval
+        (
+            self.images.row(i).mapv(|v| v as f64 / 255.0),
+            self.labels[i],
+        )
+    }
+    fn class_of(&self, i: usize) -> usize {
+        self.labels[i]
+    }
+}
+
+/**A view of some of the samples of another data set, in the given order */
+pub struct Subset<'a, D: Dataset> {
+    data: &'a D,
+    indices: Vec<usize>,
+}
+
+impl<'a, D: Dataset> Subset<'a, D> {
+    pub fn new(data: &'a D, indices: Vec<usize>) -> Self {
+        Self { data, indices }
+    }
+
+    /**Returns the indices of the samples within the original data set */
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+}
+
+impl<D: Dataset> Dataset for Subset<'_, D> {
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+    fn input_dim(&self) -> usize {
+        self.data.input_dim()
+    }
+    fn num_classes(&self) -> usize {
+        self.data.num_classes()
+    }
+    fn get(&self, i: usize) -> (Array1<f64>, usize) {
+        self.data.get(self.indices[i])
+    }
+    fn class_of(&self, i: usize) -> usize {
+        self.data.class_of(self.indices[i])
+    }
+    fn class_name(&self, class: usize) -> String {
+        self.data.class_name(class)
+    }
+}
+
+/**How many samples to hold out for validation */
+#[derive(Debug, Clone, Copy)]
+pub enum ValidationSize {
+    /**A fraction of the data set, between 0 and 1 */
+    Fraction(f64),
+    /**An exact number of samples */
+    Count(usize),
+}
+
+/**Randomly splits a data set into training and validation samples, the same way for the same seed.
+ * Exactly the given count, or the given fraction of the data set rounded to a whole number,
+ * is held out. When stratified, each class is split separately so that both parts keep
+ * (approximately) the proportions of classes in the data set.
+ * Returns the training samples followed by the validation samples.
+ */
+pub fn train_validation_split<D: Dataset>(
+    data: &D,
+    size: ValidationSize,
+    stratified: bool,
+    seed: u64,
+) -> (Subset<'_, D>, Subset<'_, D>) {
+    let held_out = match size {
+        ValidationSize::Fraction(f) if (0.0..=1.0).contains(&f) => {
+            (data.len() as f64 * f).round() as usize
+        }
+        ValidationSize::Fraction(_) => usize::MAX,
+        ValidationSize::Count(n) => n,
+    };
+    assert!(
+        held_out <= data.len(),
+        "cannot hold out more samples than the data set has"
+    );
+    let mut rng = StdRng::seed_from_u64(seed);
+    //grouping the samples by class, or into a single group
+    let mut groups = vec![Vec::new(); if stratified { data.num_classes() } else { 1 }];
+    for i in 0..data.len() {
+        let group = if stratified { data.class_of(i) } else { 0 };
+        groups[group].push(i);
+    }
+    let sizes: Vec<usize> = groups.iter().map(Vec::len).collect();
+    let (mut train, mut validation) = (Vec::new(), Vec::new());
+    for (mut group, held_out) in groups.into_iter().zip(share(held_out, &sizes)) {
+        group.shuffle(&mut rng);
+        validation.extend_from_slice(&group[..held_out]);
+        train.extend_from_slice(&group[held_out..]);
+    }
+    //mixing the classes back together
+    train.shuffle(&mut rng);
+    validation.shuffle(&mut rng);
+    (Subset::new(data, train), Subset::new(data, validation))
+}
+
+/*Shares out total between groups of the given sizes in proportion to their sizes, by the
+ * largest remainder method: each group gets the whole part of its share, then the samples
+ * left over go to the groups with the largest fractions left. The shares add up to total.*/
+fn share(total: usize, sizes: &[usize]) -> Vec<usize> {
+    let all: usize = sizes.iter().sum();
+    if all == 0 {
+        return vec![0; sizes.len()];
+    }
+    //exact shares as whole parts and remainders, in integers so nothing is lost to rounding
+    let mut shares: Vec<usize> = sizes.iter().map(|&s| total * s / all).collect();
+    let mut by_remainder: Vec<usize> = (0..sizes.len()).collect();
+    by_remainder.sort_by_key(|&i| std::cmp::Reverse(total * sizes[i] % all));
+    let left = total - shares.iter().sum::<usize>();
+    for &i in by_remainder.iter().take(left) {
+        shares[i] += 1;
+    }
+    shares
+}
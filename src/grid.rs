@@ -0,0 +1,236 @@
+use ndarray::Array2;
+use std::path::Path;
+
+use crate::read_data::{raster_error, DataError};
+
+//colors of the space between images, the labels, and the borders and labels of mistakes
+const BACKGROUND: [u8; 3] = [64, 64, 64];
+const TEXT: [u8; 3] = [230, 230, 230];
+const MISTAKE: [u8; 3] = [230, 40, 40];
+//space around each image, where the border of a mistake is drawn
+const PAD: usize = 2;
+//size of a character of the built-in font, including the space after it
+const GLYPH_WIDTH: usize = 6;
+const GLYPH_HEIGHT: usize = 7;
+
+/**How save_grid lays out and labels the images */
+#[derive(Clone, Copy)]
+pub struct GridOptions<'a> {
+    /**Images in each row of the grid */
+    pub columns: usize,
+    /**How many pixels wide each pixel of an image is drawn */
+    pub scale: usize,
+    /**The true class of each image */
+    pub labels: Option<&'a [usize]>,
+    /**The class guessed for each image. With labels, wrong guesses are drawn with a red
+     * border and labelled with the guess and the true class, separated by a slash */
+    pub predictions: Option<&'a [usize]>,
+    /**Names written for the classes, instead of their numbers */
+    pub class_names: Option<&'a [&'a str]>,
+}
+
+impl Default for GridOptions<'_> {
+    fn default() -> Self {
+        Self {
+            columns: 10,
+            scale: 2,
+            labels: None,
+            predictions: None,
+            class_names: None,
+        }
+    }
+}
+
+/**Renders many images into one grid image, each with its class written underneath,
+ * so that a batch of images (eg. the mistakes on the test set) can be reviewed at once.
+ * images - One square image per row, with pixel values between 0 and 1 (eg. rows of x_test)
+ * path - Where the grid is written, as a png, jpeg or gif chosen by its extension
+ * options - The layout and labels, which must have one entry per image
+ * Returns an error when there are no images, rather than writing an empty grid
+*/
+pub fn save_grid(
+    images: &Array2<f64>,
+    path: &Path,
+    options: &GridOptions,
+) -> Result<(), DataError> {
+    let (count, pixels) = images.dim();
+    if count == 0 {
+        return Err(DataError::NoSamples {
+            what: String::from("draw in the grid"),
+        });
+    }
+    let side = (pixels as f64).sqrt() as usize;
+    if side * side != pixels {
+        return Err(DataError::ShapeMismatch {
+            what: String::from("pixels in a square image"),
+            expected: side * side,
+            found: pixels,
+        });
+    }
+    for (what, list) in [
+        ("labels", options.labels),
+        ("predictions", options.predictions),
+    ] {
+        if let Some(list) = list.filter(|l| l.len() != count) {
+            return Err(DataError::ShapeMismatch {
+                what: format!("{} for the images", what),
+                expected: count,
+                found: list.len(),
+            });
+        }
+    }
+
+    let scale = options.scale.max(1);
+    let columns = options.columns.clamp(1, count.max(1));
+    let rows = count.div_ceil(columns);
+    let labelled = options.labels.is_some() || options.predictions.is_some();
+    //the text grows with the images, but stays readable when they are small
+    let text_scale = (scale / 2).max(1);
+    let label_height = if labelled {
+        GLYPH_HEIGHT * text_scale + 2 * PAD
+    } else {
+        0
+    };
+    let (cell_width, cell_height) = (
+        side * scale + 2 * PAD,
+        side * scale + 2 * PAD + label_height,
+    );
+    let (width, height) = (columns * cell_width, rows * cell_height);
+
+    let mut bytes = BACKGROUND
+        .iter()
+        .chain(&[255])
+        .copied()
+        .cycle()
+        .take(width * height * 4)
+        .collect::<Vec<u8>>();
+    let mut fill = |x: usize, y: usize, w: usize, h: usize, color: [u8; 3]| {
+        for row in y..y + h {
+            for col in x..x + w {
+                let at = (row * width + col) * 4;
+                bytes[at..at + 3].copy_from_slice(&color);
+            }
+        }
+    };
+
+    let name = |class: usize| match options.class_names.and_then(|n| n.get(class)) {
+        Some(name) => name.to_string(),
+        None => class.to_string(),
+    };
+    for (i, image) in images.rows().into_iter().enumerate() {
+        let (x, y) = ((i % columns) * cell_width, (i / columns) * cell_height);
+        let label = options.labels.map(|l| l[i]);
+        let guess = options.predictions.map(|p| p[i]);
+        let mistake = matches!((label, guess), (Some(l), Some(g)) if l != g);
+        if mistake {
+            fill(x, y, cell_width, side * scale + 2 * PAD, MISTAKE);
+        }
+        for (p, &v) in image.iter().enumerate() {
+            let shade = (v.clamp(0.0, 1.0) * 255.0) as u8;
+            let (px, py) = (x + PAD + (p % side) * scale, y + PAD + (p / side) * scale);
+            fill(px, py, scale, scale, [shade; 3]);
+        }
+
+        //the guess when there is one, followed by the true class if it is wrong
+        let text = match (guess, label) {
+            (Some(g), Some(l)) if mistake => format!("{}/{}", name(g), name(l)),
+            (Some(class), _) | (None, Some(class)) => name(class),
+            (None, None) => continue,
+        };
+        let color = if mistake { MISTAKE } else { TEXT };
+        let fits = (cell_width - PAD) / (GLYPH_WIDTH * text_scale);
+        let text_y = y + side * scale + 3 * PAD;
+        for (n, c) in text.chars().take(fits).enumerate() {
+            let text_x = x + PAD + n * GLYPH_WIDTH * text_scale;
+            for (r, bits) in glyph(c).iter().enumerate() {
+                for col in 0..GLYPH_WIDTH - 1 {
+                    if bits & (0x10 >> col) != 0 {
+                        let (gx, gy) = (text_x + col * text_scale, text_y + r * text_scale);
+                        fill(gx, gy, text_scale, text_scale, color);
+                    }
+                }
+            }
+        }
+    }
+
+    let grid = raster::Image {
+        width: width as i32,
+        height: height as i32,
+        bytes,
+    };
+    raster::save(&grid, &path.to_string_lossy()).map_err(|e| raster_error(path, e))
+}
+
+/*The rows of a 5x7 character, the leftmost pixel being the highest of the 5 bits.
+ * Characters that are not in the font are drawn as a question mark*/
+fn glyph(c: char) -> [u8; 7] {
+    match c {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x11, 0x1F, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        'a' => [0x00, 0x00, 0x0E, 0x01, 0x0F, 0x11, 0x0F],
+        'b' => [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x1E],
+        'c' => [0x00, 0x00, 0x0E, 0x10, 0x10, 0x11, 0x0E],
+        'd' => [0x01, 0x01, 0x0D, 0x13, 0x11, 0x11, 0x0F],
+        'e' => [0x00, 0x00, 0x0E, 0x11, 0x1F, 0x10, 0x0E],
+        'f' => [0x06, 0x09, 0x08, 0x1C, 0x08, 0x08, 0x08],
+        'g' => [0x00, 0x0F, 0x11, 0x11, 0x0F, 0x01, 0x0E],
+        'h' => [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x11],
+        'i' => [0x04, 0x00, 0x0C, 0x04, 0x04, 0x04, 0x0E],
+        'j' => [0x02, 0x00, 0x06, 0x02, 0x02, 0x12, 0x0C],
+        'k' => [0x10, 0x10, 0x12, 0x14, 0x18, 0x14, 0x12],
+        'l' => [0x0C, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'm' => [0x00, 0x00, 0x1A, 0x15, 0x15, 0x11, 0x11],
+        'n' => [0x00, 0x00, 0x16, 0x19, 0x11, 0x11, 0x11],
+        'o' => [0x00, 0x00, 0x0E, 0x11, 0x11, 0x11, 0x0E],
+        'p' => [0x00, 0x00, 0x1E, 0x11, 0x1E, 0x10, 0x10],
+        'q' => [0x00, 0x00, 0x0D, 0x13, 0x0F, 0x01, 0x01],
+        'r' => [0x00, 0x00, 0x16, 0x19, 0x10, 0x10, 0x10],
+        's' => [0x00, 0x00, 0x0E, 0x10, 0x0E, 0x01, 0x1E],
+        't' => [0x08, 0x08, 0x1C, 0x08, 0x08, 0x09, 0x06],
+        'u' => [0x00, 0x00, 0x11, 0x11, 0x11, 0x13, 0x0D],
+        'v' => [0x00, 0x00, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'w' => [0x00, 0x00, 0x11, 0x11, 0x15, 0x15, 0x0A],
+        'x' => [0x00, 0x00, 0x11, 0x0A, 0x04, 0x0A, 0x11],
+        'y' => [0x00, 0x00, 0x11, 0x11, 0x0F, 0x01, 0x0E],
+        'z' => [0x00, 0x00, 0x1F, 0x02, 0x04, 0x08, 0x1F],
+        '/' => [0x01, 0x01, 0x02, 0x04, 0x08, 0x10, 0x10],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ' ' => [0x00; 7],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    }
+}
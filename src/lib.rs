@@ -0,0 +1,9 @@
+pub mod augment;
+pub mod cache;
+pub mod dataset;
+pub mod grid;
+pub mod networks;
+pub mod normalize;
+pub mod read_data;
+pub mod synthetic;
+pub mod terminal;
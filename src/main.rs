@@ -1,88 +1,493 @@
-mod read_data;
-use read_data::*;
-
-mod networks;
-use crate::networks::{
-    neural_network::NeuralNetwork, relu_network::ReluNetwork, sigmoid_network::SigmoidNetwork,
+use ndarray::Array2;
+use ndarray_rand::rand::thread_rng;
+use neural::augment::Augmentation;
+use neural::dataset::{train_validation_split, Dataset, MnistDataset, Subset, ValidationSize};
+use neural::grid::{save_grid, GridOptions};
+use neural::networks::{
+    activation::{activation_from_name, Activation, OutputActivation},
+    init::Initializer,
+    loss::loss_from_name,
+    mlp::Mlp,
+    neural_network::{NeuralNetwork, TrainOptions},
+    optimizer::optimizer_from_name,
+    relu_network::ReluNetwork,
+    schedule::{schedule_from_name, Schedule},
+    sigmoid_network::SigmoidNetwork,
     tanh_network::TanhNetwork,
 };
+use neural::normalize::{NormalizeMode, Normalizer};
+use neural::read_data::*;
+use neural::terminal::{render_activations, render_image, RenderStyle};
+use std::path::{Path, PathBuf};
+
+//how many test images are saved with --grid
+const GRID_IMAGES: usize = 100;
+
+/**The settings chosen on the command line */
+struct Args {
+    kind: DatasetKind,
+    train: DataSource,
+    test: DataSource,
+    //fraction of the training images held out for validation
+    validation: f64,
+    //seed for shuffling the training images, which are kept in order if None
+    seed: Option<u64>,
+    //number of training images in each mini-batch
+    batch_size: usize,
+    //input normalization fit on the training images, on top of scaling pixels to 0 to 1
+    normalize: Option<NormalizeMode>,
+    //whether the training images are randomly changed every epoch
+    augment: bool,
+    //where a grid of test images labelled with the ReLU network's guesses is saved
+    grid: Option<PathBuf>,
+    //how the chosen image is drawn in the terminal, if at all
+    render: Option<RenderStyle>,
+    //whether each network's hidden layer is drawn next to its guess
+    activations: bool,
+    //size and activation function of each hidden layer of an extra multi-layer network,
+    //which is not trained if empty
+    hidden: Vec<(usize, Box<dyn Activation>)>,
+    //activation function of every network's output layer, instead of each network's default
+    output: Option<OutputActivation>,
+    //name of the loss every network is trained with, instead of each network's default
+    loss: Option<String>,
+    //name of the optimizer every network is trained with, plain gradient descent if None
+    optimizer: Option<String>,
+    //how the learning rate changes from epoch to epoch, constant if None
+    schedule: Option<Box<dyn Schedule>>,
+    //how every network's weights and biases are initialized, instead of each layer's default
+    init: Option<(Initializer, Initializer)>,
+}
+
+/**Prints how the program is used and exits */
+fn usage() -> ! {
+    eprintln!("Usage: neural [--dataset <name>] [--data-dir <dir>] [--train <file>] [--test <file>] [--validation <fraction>] [--seed <n>] [--batch-size <n>] [--normalize <mode>] [--augment] [--grid <file>] [--render <style>] [--activations] [--hidden <layers>] [--output <name>] [--loss <name>] [--optimizer <name>] [--schedule <name>] [--init <name>]");
+    std::process::exit(2);
+}
+
+/**Determines which data set is used and where its training and testing data are read from.
+ * --dataset <name> chooses between mnist (the default), fashion-mnist, kmnist and the
+ * emnist-balanced, emnist-digits and emnist-letters splits.
+ * --data-dir <dir> overrides the data set's directory (default: mnist_data or MNIST_DATA_DIR
+ * for MNIST, fashion_mnist_data, kmnist_data or emnist_data for the others),
+ * while --train <file> and --test <file> point at individual csv or IDX image files.
+ * --validation <fraction> holds out part of the training images to report validation accuracy,
+ * --seed <n> shuffles the training images every epoch, and --batch-size <n> trains on
+ * mini-batches of n images at once (default 1).
+ * --normalize <mode> standardizes the inputs with global, per-pixel or min-max statistics,
+ * --augment trains on randomly rotated, shifted, scaled, distorted and noisy images,
+ * --grid <file> saves the first test images labelled with the ReLU network's guesses.
+ * --render <style> draws the chosen image in the terminal as ascii, blocks or color,
+ * and --activations draws each network's hidden layer next to its guess.
+ * --hidden <layers> also trains a network with hidden layers of the given sizes, each
+ * optionally followed by its activation function (eg. 256:relu,128:tanh, sigmoid by default).
+ * --output <name> gives every network a softmax, sigmoid or identity output layer along with
+ * the loss suited to it (cross-entropy, binary-cross-entropy or mse), and --loss <name> trains
+ * every network with cross-entropy, binary-cross-entropy, mse, hinge or focal (eg. focal:2)
+ * instead of the loss each network or output layer uses by default.
+ * --optimizer <name> updates the weights with sgd (the default), momentum, nesterov, rmsprop,
+ * adagrad, adam or adamw (eg. momentum:0.95), and --schedule <name> changes the learning rate
+ * every epoch with step, exponential, cosine, warmup, one-cycle or plateau (eg. step:10:0.5).
+ * --init <name> initializes every layer's weights with xavier-uniform, xavier-normal,
+ * he-uniform, he-normal, lecun-uniform, lecun-normal, orthogonal, zeros, constant or uniform
+ * instead of the one suited to its activation, optionally followed by the biases' initializer
+ * (eg. he-normal,constant:0.01, zeros by default) */
+fn parse_args() -> Args {
+    let mut kind = DatasetKind::Mnist;
+    let mut dir = None;
+    let (mut train, mut test) = (None, None);
+    let (mut validation, mut seed, mut normalize) = (0.0, None, None);
+    let mut batch_size = 1;
+    let (mut augment, mut grid) = (false, None);
+    let (mut render, mut activations, mut hidden) = (None, false, Vec::new());
+    let (mut output, mut loss, mut optimizer, mut schedule) = (None, None, None, None);
+    let mut init = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next().unwrap_or_else(|| {
+                eprintln!("Missing value for {}.", arg);
+                std::process::exit(2);
+            })
+        };
+        match arg.as_str() {
+            "--dataset" => {
+                let name = value();
+                kind = DatasetKind::from_name(&name).unwrap_or_else(|| {
+                    eprintln!("Unknown data set {}.", name);
+                    std::process::exit(2);
+                })
+            }
+            "--data-dir" => dir = Some(PathBuf::from(value())),
+            "--train" => train = Some(DataSource::from_file(&PathBuf::from(value()))),
+            "--test" => test = Some(DataSource::from_file(&PathBuf::from(value()))),
+            "--validation" => match value().parse() {
+                Ok(v) if (0.0..1.0).contains(&v) => validation = v,
+                _ => usage(),
+            },
+            "--seed" => seed = Some(value().parse().unwrap_or_else(|_| usage())),
+            "--batch-size" => match value().parse() {
+                Ok(n) if n > 0 => batch_size = n,
+                _ => usage(),
+            },
+            "--augment" => augment = true,
+            "--grid" => grid = Some(PathBuf::from(value())),
+            "--render" => {
+                render = Some(RenderStyle::from_name(&value()).unwrap_or_else(|| usage()))
+            }
+            "--activations" => activations = true,
+            "--hidden" => {
+                hidden = value()
+                    .split(',')
+                    .map(|layer| {
+                        let (size, activation) = layer
+                            .trim()
+                            .split_once(':')
+                            .unwrap_or((layer.trim(), "sigmoid"));
+                        match (size.parse(), activation_from_name(activation)) {
+                            (Ok(size), Some(activation)) if size > 0 => (size, activation),
+                            _ => usage(),
+                        }
+                    })
+                    .collect()
+            }
+            "--output" => {
+                output = Some(OutputActivation::from_name(&value()).unwrap_or_else(|| usage()))
+            }
+            "--loss" => {
+                let name = value();
+                loss_from_name(&name).unwrap_or_else(|| usage());
+                loss = Some(name);
+            }
+            "--optimizer" => {
+                let name = value();
+                optimizer_from_name(&name).unwrap_or_else(|| usage());
+                optimizer = Some(name);
+            }
+            "--schedule" => {
+                schedule = Some(schedule_from_name(&value()).unwrap_or_else(|| usage()))
+            }
+            "--init" => {
+                let name = value();
+                let (weights, bias) = name.split_once(',').unwrap_or((&name, "zeros"));
+                let initializer = |name| Initializer::from_name(name).unwrap_or_else(|| usage());
+                init = Some((initializer(weights), initializer(bias)));
+            }
+            "--normalize" => {
+                normalize = Some(NormalizeMode::from_name(&value()).unwrap_or_else(|| usage()))
+            }
+            _ => usage(),
+        }
+    }
+    //each data set has its own default directory, as several share the same file names
+    let dir = dir.unwrap_or_else(|| data_dir_from_env(kind));
+    Args {
+        kind,
+        train: train.unwrap_or_else(|| DataSource::training(&dir, kind)),
+        test: test.unwrap_or_else(|| DataSource::testing(&dir, kind)),
+        validation,
+        seed,
+        batch_size,
+        normalize,
+        augment,
+        grid,
+        render,
+        activations,
+        hidden,
+        output,
+        loss,
+        optimizer,
+        schedule,
+        init,
+    }
+}
+
+/**Replaces the network's default output activation, loss, optimizer and initial weights
+ * with the ones chosen by --output, --loss, --optimizer and --init, if any. An output
+ * activation chosen without a loss brings the loss suited to it */
+fn set_chosen_options(
+    net: &mut impl NeuralNetwork,
+    output: Option<OutputActivation>,
+    loss: &Option<String>,
+    optimizer: &Option<String>,
+    init: Option<(Initializer, Initializer)>,
+) {
+    if let Some(output) = output {
+        net.set_output_activation(output);
+        net.set_loss(output.default_loss());
+    }
+    if let Some(loss) = loss.as_deref().and_then(loss_from_name) {
+        net.set_loss(loss);
+    }
+    if let Some(optimizer) = optimizer.as_deref().and_then(optimizer_from_name) {
+        net.set_optimizer(optimizer);
+    }
+    if let Some((weights, bias)) = init {
+        //the same initializer for every layer, which has a weight and a bias parameter
+        let layers = net.parameters().len() / 2;
+        net.initialize(&vec![weights; layers], bias, &mut thread_rng());
+    }
+}
 
 fn main() {
+    let args = parse_args();
+    let kind = args.kind;
     println!("This program will train Neural Networks on the MNIST Data Set using different activation functions with the same parameters for comparison.");
     let quantity = 10000;
     println!("Loading in training data from file ({} images).", quantity);
-    let (x_train, y_train) = get_training_data(quantity);
+    let train_data = MnistDataset::load(&args.train, kind, Some(quantity)).unwrap_or_else(|e| {
+        eprintln!("Could not load training data: {}", e);
+        std::process::exit(1);
+    });
+    //holding out part of the training images to check for overfitting
+    let (train_part, val_part) = if args.validation > 0.0 {
+        train_validation_split(
+            &train_data,
+            ValidationSize::Fraction(args.validation),
+            true,
+            args.seed.unwrap_or(0),
+        )
+    } else {
+        (
+            Subset::new(&train_data, (0..train_data.len()).collect()),
+            Subset::new(&train_data, Vec::new()),
+        )
+    };
+    let (x_val, y_val) = val_part.to_arrays();
+    //the statistics come from the training images only, and each network keeps its own copy
+    let normalizer = args
+        .normalize
+        .map(|mode| Normalizer::fit(&train_part, mode))
+        .transpose()
+        .unwrap_or_else(|e| {
+            eprintln!("Could not fit the normalizer: {}", e);
+            std::process::exit(1);
+        });
+    let augmentation = Augmentation::digits(args.seed.unwrap_or(0));
+    let options = TrainOptions {
+        shuffle_seed: args.seed,
+        validation: (!val_part.is_empty()).then_some((&x_val, &y_val)),
+        augmentation: args.augment.then_some(&augmentation),
+        batch_size: Some(args.batch_size),
+        schedule: args.schedule.as_deref(),
+    };
     //parameters
     let epochs = 3;
     let learn_rate = 0.01;
     println!(
-        "Training data and parameters set. ({} epochs, {} learn rate).\n",
-        epochs, learn_rate
+        "Training data and parameters set. ({} epochs, {} learn rate with a {} schedule, batch size {}, {} optimizer).\n",
+        epochs,
+        learn_rate,
+        args.schedule.as_ref().map_or(String::from("constant"), |s| s.name()),
+        args.batch_size,
+        args.optimizer.as_deref().unwrap_or("sgd")
     );
 
     //Network using Sigmoid for its activation function
     println!("Training Neural Network 1 using Sigmoid...");
     //creating
-    let mut sig_net = SigmoidNetwork::new();
+    let mut sig_net = SigmoidNetwork::for_dataset(&train_data);
+    sig_net.set_normalizer(normalizer.clone());
+    set_chosen_options(
+        &mut sig_net,
+        args.output,
+        &args.loss,
+        &args.optimizer,
+        args.init,
+    );
     //training
-    sig_net.gradient_descent(&x_train, &y_train, epochs, learn_rate);
+    sig_net.train_with(&train_part, epochs, learn_rate, &options);
     println!("Sigmoid Network training complete.\n");
 
     //Network using Tanh for its activation function
     println!("Training Neural Network 2 using Tanh...");
     //creating
-    let mut tanh_net = TanhNetwork::new();
+    let mut tanh_net = TanhNetwork::for_dataset(&train_data);
+    tanh_net.set_normalizer(normalizer.clone());
+    set_chosen_options(
+        &mut tanh_net,
+        args.output,
+        &args.loss,
+        &args.optimizer,
+        args.init,
+    );
     //training
-    tanh_net.gradient_descent(&x_train, &y_train, epochs, learn_rate);
+    tanh_net.train_with(&train_part, epochs, learn_rate, &options);
     println!("Tanh Network training complete.\n");
 
     //Network using ReLU for its activation function
     println!("Training Neural Network 3 using ReLU...");
     //creating
-    let mut relu_net = ReluNetwork::new();
+    let mut relu_net = ReluNetwork::for_dataset(&train_data);
+    relu_net.set_normalizer(normalizer.clone());
+    set_chosen_options(
+        &mut relu_net,
+        args.output,
+        &args.loss,
+        &args.optimizer,
+        args.init,
+    );
     //training
-    relu_net.gradient_descent(&x_train, &y_train, epochs, learn_rate);
+    relu_net.train_with(&train_part, epochs, learn_rate, &options);
     println!("ReLU Network training complete.\n");
 
+    //Network with any number of hidden layers, each with its own activation function
+    let mut mlp_net = (!args.hidden.is_empty()).then(|| {
+        let layers: Vec<String> = args
+            .hidden
+            .iter()
+            .map(|(size, activation)| format!("{} {}", size, activation.name()))
+            .collect();
+        println!(
+            "Training Neural Network 4 with hidden layers {}...",
+            layers.join(", ")
+        );
+        //creating, with a sigmoid output layer unless --output is given
+        let mut sizes = vec![train_data.input_dim()];
+        let mut activations = Vec::new();
+        for (size, activation) in args.hidden {
+            sizes.push(size);
+            activations.push(activation);
+        }
+        sizes.push(train_data.num_classes());
+        let mut net = Mlp::with_activations(&sizes, activations);
+        net.set_normalizer(normalizer.clone());
+        set_chosen_options(
+            &mut net,
+            args.output,
+            &args.loss,
+            &args.optimizer,
+            args.init,
+        );
+        //training
+        net.train_with(&train_part, epochs, learn_rate, &options);
+        println!("Multi-layer Network training complete.\n");
+        net
+    });
+
     //Test network by making guesses on test set which it has not been trained on
     println!("Acquiring testing data from file.");
-    let (x_test, y_test) = get_testing_data();
+    let test_data = MnistDataset::load(&args.test, kind, None).unwrap_or_else(|e| {
+        eprintln!("Could not load testing data: {}", e);
+        std::process::exit(1);
+    });
+    if let Some(path) = &args.grid {
+        //the guesses are checked against the true labels, and mistakes drawn in red
+        let count = test_data.len().min(GRID_IMAGES);
+        let (x_test, _) = Subset::new(&test_data, (0..count).collect()).to_arrays();
+        let labels: Vec<usize> = (0..count).map(|i| test_data.class_of(i)).collect();
+        let guesses: Vec<usize> = x_test
+            .rows()
+            .into_iter()
+            .map(|image| relu_net.make_guess(&image.to_owned()))
+            .collect();
+        let options = GridOptions {
+            labels: Some(&labels),
+            predictions: Some(&guesses),
+            class_names: Some(kind.class_names()),
+            ..GridOptions::default()
+        };
+        match save_grid(&x_test, path, &options) {
+            Ok(()) => println!("Saved {} test images to {}.", count, path.display()),
+            Err(e) => println!("Could not save the grid: {}", e),
+        }
+    }
+
+    let last = test_data.len() as i64 - 1;
+    let name = |class| test_data.class_name(class);
     loop {
-        //Select an image from set
-        println!("\nEnter 0 to 9999 to test an image from test set (or -1 to exit):");
+        //Select an image from set, or from a file
+        println!(
+            "\nEnter 0 to {} to test an image from test set, or the path of an image file (or -1 to exit):",
+            last
+        );
         let mut input = String::new();
         let _read = std::io::stdin()
             .read_line(&mut input)
             .expect("failed to read input");
-        let idx: i64 = input.trim().parse().unwrap();
+        let input = input.trim();
 
         //exit loop condition
-        if idx == -1 {
+        if input.is_empty() || input == "-1" {
             println!("Exiting...");
             break;
         }
 
-        //invalid input check
-        if idx < 0 || idx > 9999 {
-            println!("Invalid input try again.");
-            continue;
+        let (image, label) = match input.parse::<i64>() {
+            //invalid input check
+            Ok(idx) if !(0..=last).contains(&idx) => {
+                println!("Invalid input try again.");
+                continue;
+            }
+            Ok(idx) => {
+                println!("Testing image #{} in test set:", idx);
+                let (image, label) = test_data.get(idx as usize);
+                (image, Some(label))
+            }
+            Err(_) => match load_image(Path::new(input)) {
+                Ok(image) => {
+                    println!("Testing image {}:", input);
+                    (image, None)
+                }
+                Err(e) => {
+                    println!("Could not load the image: {}", e);
+                    continue;
+                }
+            },
+        };
+
+        //showing the image chosen
+        if let Err(e) = show_image(&image) {
+            println!("Could not save the image: {}", e);
         }
 
-        //Network will guess the image
-        println!("Testing image #{} in test set:", idx);
-        let image = x_test.row(idx as usize).to_owned();
-        let label = y_test.get(idx as usize).unwrap();
+        //Making the guess, keeping each network's hidden layer for --activations
+        let mut report = Vec::new();
+        let mut guess = |network: &str, guess: usize, hidden: Array2<f64>| {
+            report.push(format!(
+                "The {} Network guessed that this is a: {}",
+                network,
+                name(guess)
+            ));
+            if args.activations {
+                let style = args.render.unwrap_or(RenderStyle::Blocks);
+                let strip = render_activations(&hidden.column(0).to_owned(), style);
+                report.push(format!("  hidden layer: {}", strip));
+            }
+        };
+        guess(
+            "Sigmoid",
+            sig_net.make_guess(&image),
+            sig_net.get_hid_layer(),
+        );
+        guess(
+            "Tanh",
+            tanh_net.make_guess(&image),
+            tanh_net.get_hid_layer(),
+        );
+        guess(
+            "ReLU",
+            relu_net.make_guess(&image),
+            relu_net.get_hid_layer(),
+        );
+        if let Some(net) = mlp_net.as_mut() {
+            guess("Multi-layer", net.make_guess(&image), net.get_hid_layer());
+        }
+        if let Some(label) = label {
+            report.push(format!("The image is actually a: {}", name(label)));
+        }
 
-        //showing the image chosen
-        show_image(&image);
-
-        //Making the guess
-        let sig_guess = sig_net.make_guess(&image);
-        let tanh_guess = tanh_net.make_guess(&image);
-        let relu_guess = relu_net.make_guess(&image);
-        println!("The Sigmoid Network guessed that this is a: {}", sig_guess);
-        println!("The Tanh Network guessed that this is a: {}", tanh_guess);
-        println!("The ReLU Network guessed that this is a: {}", relu_guess);
-        println!("The digit is actually a: {}", label);
+        //printing the guesses next to the image when it is drawn in the terminal
+        match args.render {
+            Some(style) => {
+                let picture = render_image(&image, style);
+                let start = picture.len().saturating_sub(report.len()) / 2;
+                for (i, line) in picture.iter().enumerate() {
+                    let text = i.checked_sub(start).and_then(|i| report.get(i));
+                    println!("{}  {}", line, text.map_or("", |t| t.as_str()));
+                }
+            }
+            None => report.iter().for_each(|line| println!("{}", line)),
+        }
     }
 }
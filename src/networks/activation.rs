@@ -0,0 +1,337 @@
+use ndarray::{Array1, Array2, Axis};
+
+use crate::networks::init::Initializer;
+use crate::networks::loss::{BinaryCrossEntropy, CrossEntropy, Loss, MeanSquaredError};
+
+//constants of SELU, which keep the mean and variance of the activations close to 0 and 1
+const SELU_LAMBDA: f64 = 1.050_700_987_355_480_5;
+const SELU_ALPHA: f64 = 1.673_263_242_354_377_2;
+//sqrt(2 / pi), used by the tanh approximation of GELU
+const GELU_K: f64 = 0.797_884_560_802_865_4;
+const GELU_CUBIC: f64 = 0.044_715;
+
+/**An activation function applied element-wise to the weighted sums of a layer.
+ * Both functions take the weighted sums (the values before the activation is applied),
+ * so any activation can be used for any layer at runtime. */
+pub trait Activation {
+    /**Applies the activation function to each weighted sum */
+    fn forward(&self, x: &Array2<f64>) -> Array2<f64>;
+    /**The derivative of the activation function at each weighted sum */
+    fn derivative(&self, x: &Array2<f64>) -> Array2<f64>;
+    /**The name the activation is chosen by in activation_from_name */
+    fn name(&self) -> String;
+    /**How the weights of a layer using this activation are initialized by default,
+     * Xavier unless the activation is better suited to another */
+    fn initializer(&self) -> Initializer {
+        Initializer::XavierUniform
+    }
+}
+
+/**Returns the activation with the given name: sigmoid, tanh, relu, leaky-relu, elu, selu,
+ * gelu, swish or softplus. The slope of leaky-relu for negative sums (default 0.01) and the
+ * alpha of elu (default 1) can follow the name after a colon, eg. leaky-relu:0.1,
+ * and must be finite */
+pub fn activation_from_name(name: &str) -> Option<Box<dyn Activation>> {
+    let (name, param) = match name.split_once(':') {
+        Some((name, param)) => (name, Some(param.parse::<f64>().ok()?)),
+        None => (name, None),
+    };
+    if param.is_some_and(|p| !p.is_finite()) {
+        return None;
+    }
+    let activation: Box<dyn Activation> = match (name, param) {
+        ("leaky-relu", alpha) => Box::new(LeakyRelu {
+            alpha: alpha.unwrap_or(0.01),
+        }),
+        ("elu", alpha) => Box::new(Elu {
+            alpha: alpha.unwrap_or(1.0),
+        }),
+        (_, Some(_)) => return None,
+        ("sigmoid", None) => Box::new(Sigmoid),
+        ("tanh", None) => Box::new(Tanh),
+        ("relu", None) => Box::new(Relu),
+        ("selu", None) => Box::new(Selu),
+        ("gelu", None) => Box::new(Gelu),
+        ("swish", None) => Box::new(Swish),
+        ("softplus", None) => Box::new(Softplus),
+        _ => return None,
+    };
+    Some(activation)
+}
+
+/*The sigmoid function of a single value, used by several activations*/
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/**1 / (1 + e^-x), between 0 and 1 */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sigmoid;
+
+impl Activation for Sigmoid {
+    fn forward(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(sigmoid)
+    }
+    fn derivative(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(|v| sigmoid(v) * (1.0 - sigmoid(v)))
+    }
+    fn name(&self) -> String {
+        String::from("sigmoid")
+    }
+}
+
+/**The hyperbolic tangent, between -1 and 1 */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tanh;
+
+impl Activation for Tanh {
+    fn forward(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(f64::tanh)
+    }
+    fn derivative(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(|v| 1.0 - v.tanh().powi(2))
+    }
+    fn name(&self) -> String {
+        String::from("tanh")
+    }
+}
+
+/**max(0, x) */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Relu;
+
+impl Activation for Relu {
+    fn forward(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(|v| v.max(0.0))
+    }
+    fn derivative(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(|v| if v > 0.0 { 1.0 } else { 0.0 })
+    }
+    fn name(&self) -> String {
+        String::from("relu")
+    }
+    fn initializer(&self) -> Initializer {
+        Initializer::HeUniform
+    }
+}
+
+/**Like ReLU, but with a small slope alpha for negative values so units never stop learning */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LeakyRelu {
+    pub alpha: f64,
+}
+
+impl Activation for LeakyRelu {
+    fn forward(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(|v| if v > 0.0 { v } else { self.alpha * v })
+    }
+    fn derivative(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(|v| if v > 0.0 { 1.0 } else { self.alpha })
+    }
+    fn name(&self) -> String {
+        format!("leaky-relu:{}", self.alpha)
+    }
+    fn initializer(&self) -> Initializer {
+        Initializer::HeUniform
+    }
+}
+
+/**Exponential linear unit: x for positive values, alpha * (e^x - 1) for negative ones */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Elu {
+    pub alpha: f64,
+}
+
+impl Activation for Elu {
+    fn forward(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(|v| if v > 0.0 { v } else { self.alpha * v.exp_m1() })
+    }
+    fn derivative(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(|v| if v > 0.0 { 1.0 } else { self.alpha * v.exp() })
+    }
+    fn name(&self) -> String {
+        format!("elu:{}", self.alpha)
+    }
+    fn initializer(&self) -> Initializer {
+        Initializer::HeUniform
+    }
+}
+
+/**Scaled exponential linear unit, an ELU scaled so that activations normalize themselves */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Selu;
+
+impl Activation for Selu {
+    fn forward(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(|v| SELU_LAMBDA * if v > 0.0 { v } else { SELU_ALPHA * v.exp_m1() })
+    }
+    fn derivative(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(|v| SELU_LAMBDA * if v > 0.0 { 1.0 } else { SELU_ALPHA * v.exp() })
+    }
+    fn name(&self) -> String {
+        String::from("selu")
+    }
+    fn initializer(&self) -> Initializer {
+        Initializer::LecunNormal
+    }
+}
+
+/**Gaussian error linear unit, x weighted by how likely a standard normal value is below it.
+ * Uses the usual tanh approximation */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gelu;
+
+impl Activation for Gelu {
+    fn forward(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(|v| 0.5 * v * (1.0 + (GELU_K * (v + GELU_CUBIC * v.powi(3))).tanh()))
+    }
+    fn derivative(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(|v| {
+            let t = (GELU_K * (v + GELU_CUBIC * v.powi(3))).tanh();
+            let inner = GELU_K * (1.0 + 3.0 * GELU_CUBIC * v * v);
+            0.5 * (1.0 + t) + 0.5 * v * (1.0 - t * t) * inner
+        })
+    }
+    fn name(&self) -> String {
+        String::from("gelu")
+    }
+    fn initializer(&self) -> Initializer {
+        Initializer::HeUniform
+    }
+}
+
+/**x * sigmoid(x), also known as SiLU */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Swish;
+
+impl Activation for Swish {
+    fn forward(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(|v| v * sigmoid(v))
+    }
+    fn derivative(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(|v| {
+            let s = sigmoid(v);
+            s + v * s * (1.0 - s)
+        })
+    }
+    fn name(&self) -> String {
+        String::from("swish")
+    }
+    fn initializer(&self) -> Initializer {
+        Initializer::HeUniform
+    }
+}
+
+/**ln(1 + e^x), a smooth version of ReLU */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Softplus;
+
+impl Activation for Softplus {
+    fn forward(&self, x: &Array2<f64>) -> Array2<f64> {
+        //written so that e^x cannot overflow for large values
+        x.mapv(|v| v.max(0.0) + (-v.abs()).exp().ln_1p())
+    }
+    fn derivative(&self, x: &Array2<f64>) -> Array2<f64> {
+        x.mapv(sigmoid)
+    }
+    fn name(&self) -> String {
+        String::from("softplus")
+    }
+    fn initializer(&self) -> Initializer {
+        Initializer::HeUniform
+    }
+}
+
+/**The activation function of the output layer, which unlike the hidden layers' activations
+ * may combine every weighted sum of a sample, as softmax does */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputActivation {
+    /**Turns the outputs into probabilities of each class that add up to 1 */
+    Softmax,
+    /**Squashes each output between 0 and 1 on its own */
+    Sigmoid,
+    /**Uses the weighted sums as they are */
+    Identity,
+}
+
+impl OutputActivation {
+    /**Returns the output activation with the given name (softmax, sigmoid or identity) */
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "softmax" => Some(Self::Softmax),
+            "sigmoid" => Some(Self::Sigmoid),
+            "identity" => Some(Self::Identity),
+            _ => None,
+        }
+    }
+
+    /**The name the output activation is chosen by in from_name */
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Softmax => "softmax",
+            Self::Sigmoid => "sigmoid",
+            Self::Identity => "identity",
+        }
+    }
+
+    /**How the weights of the output layer are initialized by default */
+    pub fn initializer(&self) -> Initializer {
+        //every output activation is centered on or linear around 0, as Xavier assumes
+        Initializer::XavierUniform
+    }
+
+    /**The loss suited to the outputs: categorical cross-entropy for the probabilities of
+     * softmax, binary cross-entropy for those of sigmoid, and mean squared error otherwise */
+    pub fn default_loss(&self) -> Box<dyn Loss> {
+        match self {
+            Self::Softmax => Box::new(CrossEntropy),
+            Self::Sigmoid => Box::new(BinaryCrossEntropy),
+            Self::Identity => Box::new(MeanSquaredError),
+        }
+    }
+
+    /**Applies the output activation to the weighted sums, with one sample per column */
+    pub fn forward(&self, x: &Array2<f64>) -> Array2<f64> {
+        match self {
+            Self::Softmax => softmax(x),
+            Self::Sigmoid => Sigmoid.forward(x),
+            Self::Identity => x.to_owned(),
+        }
+    }
+
+    /**Converts the gradient of a loss with respect to the outputs into the gradient with
+     * respect to the weighted sums, from the outputs of forward. Losses that pair with an
+     * output activation (eg. cross-entropy with softmax) skip this in Loss::output_error */
+    pub fn error(&self, out: &Array2<f64>, grad: &Array2<f64>) -> Array2<f64> {
+        match self {
+            //every output of softmax depends on every weighted sum of its sample
+            Self::Softmax => out * &(grad - &(grad * out).sum_axis(Axis(0))),
+            Self::Sigmoid => grad * &(out * &(1.0 - out)),
+            Self::Identity => grad.to_owned(),
+        }
+    }
+}
+
+/**The log of the sum of the exponentials of each column, ln(sum(e^x)).
+ * The largest value is taken out before exponentiating, so it cannot overflow */
+pub fn log_sum_exp(x: &Array2<f64>) -> Array1<f64> {
+    x.map_axis(Axis(0), |column| {
+        let max = column.fold(f64::NEG_INFINITY, |m, &v| m.max(v));
+        if max.is_finite() {
+            max + column.mapv(|v| (v - max).exp()).sum().ln()
+        } else {
+            max
+        }
+    })
+}
+
+/**The log of softmax of each column, x - log_sum_exp(x) */
+pub fn log_softmax(x: &Array2<f64>) -> Array2<f64> {
+    x - &log_sum_exp(x)
+}
+
+/**Softmax of each column, e^x / sum(e^x), computed through log_sum_exp so that
+ * large weighted sums do not overflow */
+pub fn softmax(x: &Array2<f64>) -> Array2<f64> {
+    log_softmax(x).mapv(f64::exp)
+}
@@ -0,0 +1,125 @@
+use ndarray::{Array, Array2};
+use ndarray_rand::rand::Rng;
+use ndarray_rand::rand_distr::{Normal, Uniform};
+use ndarray_rand::RandomExt;
+
+/**How the weights or biases of a layer are first set, before training.
+ * The random initializers are scaled by the number of inputs (fan-in) and outputs (fan-out)
+ * of the layer, so that values neither grow nor shrink from layer to layer. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Initializer {
+    /**Xavier/Glorot, uniform within ±sqrt(6 / (fan_in + fan_out)), suited to sigmoid and tanh */
+    XavierUniform,
+    /**Xavier/Glorot, normal with a standard deviation of sqrt(2 / (fan_in + fan_out)) */
+    XavierNormal,
+    /**He/Kaiming, uniform within ±sqrt(6 / fan_in), suited to ReLU and its variants */
+    HeUniform,
+    /**He/Kaiming, normal with a standard deviation of sqrt(2 / fan_in) */
+    HeNormal,
+    /**LeCun, uniform within ±sqrt(3 / fan_in) */
+    LecunUniform,
+    /**LeCun, normal with a standard deviation of sqrt(1 / fan_in), suited to SELU */
+    LecunNormal,
+    /**A random matrix whose rows (or columns, whichever there are fewer of) are orthonormal */
+    Orthogonal,
+    /**Every value 0, the usual choice for biases */
+    Zeros,
+    /**Every value the same */
+    Constant(f64),
+    /**Uniform within ±limit whatever the size of the layer */
+    Uniform(f64),
+}
+
+impl Initializer {
+    /**Returns the initializer with the given name: xavier-uniform, xavier-normal, he-uniform,
+     * he-normal, lecun-uniform, lecun-normal, orthogonal, zeros, constant or uniform.
+     * The value of constant (default 0) and the limit of uniform (default 0.5)
+     * can follow the name after a colon, eg. constant:0.1. The value must be finite,
+     * and the limit must also not be negative */
+    pub fn from_name(name: &str) -> Option<Self> {
+        let (name, param) = match name.split_once(':') {
+            Some((name, param)) => (
+                name,
+                Some(param.parse::<f64>().ok().filter(|p| p.is_finite())?),
+            ),
+            None => (name, None),
+        };
+        match (name, param) {
+            ("constant", value) => Some(Self::Constant(value.unwrap_or(0.0))),
+            ("uniform", Some(limit)) if limit < 0.0 => None,
+            ("uniform", limit) => Some(Self::Uniform(limit.unwrap_or(0.5))),
+            (_, Some(_)) => None,
+            ("xavier-uniform", None) => Some(Self::XavierUniform),
+            ("xavier-normal", None) => Some(Self::XavierNormal),
+            ("he-uniform", None) => Some(Self::HeUniform),
+            ("he-normal", None) => Some(Self::HeNormal),
+            ("lecun-uniform", None) => Some(Self::LecunUniform),
+            ("lecun-normal", None) => Some(Self::LecunNormal),
+            ("orthogonal", None) => Some(Self::Orthogonal),
+            ("zeros", None) => Some(Self::Zeros),
+            _ => None,
+        }
+    }
+
+    /**The name the initializer is chosen by in from_name */
+    pub fn name(&self) -> String {
+        match self {
+            Self::XavierUniform => String::from("xavier-uniform"),
+            Self::XavierNormal => String::from("xavier-normal"),
+            Self::HeUniform => String::from("he-uniform"),
+            Self::HeNormal => String::from("he-normal"),
+            Self::LecunUniform => String::from("lecun-uniform"),
+            Self::LecunNormal => String::from("lecun-normal"),
+            Self::Orthogonal => String::from("orthogonal"),
+            Self::Zeros => String::from("zeros"),
+            Self::Constant(value) => format!("constant:{}", value),
+            Self::Uniform(limit) => format!("uniform:{}", limit),
+        }
+    }
+
+    /**Returns new values for a matrix of the given shape, which is (fan_out, fan_in)
+     * for the weights of a layer and (fan_out, 1) for its bias, drawn from rng so that
+     * a seeded rng always gives the same values */
+    pub fn init<R: Rng>(&self, shape: (usize, usize), rng: &mut R) -> Array2<f64> {
+        let (fan_out, fan_in) = (shape.0 as f64, shape.1 as f64);
+        let uniform = |limit: f64, rng: &mut R| {
+            Array::random_using(shape, Uniform::new_inclusive(-limit, limit), rng)
+        };
+        let normal =
+            |std: f64, rng: &mut R| Array::random_using(shape, Normal::new(0.0, std).unwrap(), rng);
+        match *self {
+            Self::XavierUniform => uniform((6.0 / (fan_in + fan_out)).sqrt(), rng),
+            Self::XavierNormal => normal((2.0 / (fan_in + fan_out)).sqrt(), rng),
+            Self::HeUniform => uniform((6.0 / fan_in).sqrt(), rng),
+            Self::HeNormal => normal((2.0 / fan_in).sqrt(), rng),
+            Self::LecunUniform => uniform((3.0 / fan_in).sqrt(), rng),
+            Self::LecunNormal => normal((1.0 / fan_in).sqrt(), rng),
+            Self::Orthogonal => orthogonal(shape, rng),
+            Self::Zeros => Array2::zeros(shape),
+            Self::Constant(value) => Array2::from_elem(shape, value),
+            Self::Uniform(limit) => uniform(limit, rng),
+        }
+    }
+}
+
+/*A random matrix with orthonormal columns if it is tall, or orthonormal rows if it is wide,
+ * found with Gram-Schmidt on normally distributed values*/
+fn orthogonal(shape: (usize, usize), rng: &mut impl Rng) -> Array2<f64> {
+    let (rows, cols) = shape;
+    let tall = (rows.max(cols), rows.min(cols));
+    let mut m: Array2<f64> = Array::random_using(tall, Normal::new(0.0, 1.0).unwrap(), rng);
+    for j in 0..tall.1 {
+        for k in 0..j {
+            let projection = m.column(j).dot(&m.column(k));
+            let previous = m.column(k).to_owned();
+            m.column_mut(j).scaled_add(-projection, &previous);
+        }
+        let norm = m.column(j).dot(&m.column(j)).sqrt();
+        m.column_mut(j).mapv_inplace(|v| v / norm);
+    }
+    if rows >= cols {
+        m
+    } else {
+        m.reversed_axes()
+    }
+}
@@ -0,0 +1,202 @@
+use ndarray::{Array2, Axis, Zip};
+
+use crate::networks::activation::OutputActivation;
+
+//outputs are kept this far from 0 and 1 before taking logarithms or dividing by them
+const EPSILON: f64 = 1e-12;
+
+/**A loss function comparing the output layer of a network with the one hot encoded labels.
+ * Both functions take the output layer (the values after its activation function),
+ * with one sample per column. */
+pub trait Loss {
+    /**The loss summed over the samples */
+    fn value(&self, out: &Array2<f64>, lab: &Array2<f64>) -> f64;
+    /**The gradient of the summed loss with respect to each output */
+    fn gradient(&self, out: &Array2<f64>, lab: &Array2<f64>) -> Array2<f64>;
+    /**The name the loss is chosen by in loss_from_name */
+    fn name(&self) -> String;
+
+    /**The gradient of the summed loss with respect to the weighted sums of the output layer,
+     * by default the gradient with respect to the outputs passed back through the activation */
+    fn output_error(
+        &self,
+        activation: OutputActivation,
+        out: &Array2<f64>,
+        lab: &Array2<f64>,
+    ) -> Array2<f64> {
+        activation.error(out, &self.gradient(out, lab))
+    }
+}
+
+/**Returns the loss with the given name: cross-entropy, binary-cross-entropy, mse, hinge
+ * or focal. The gamma of focal (default 2) can follow the name after a colon, eg. focal:1.5,
+ * and must be finite and not negative */
+pub fn loss_from_name(name: &str) -> Option<Box<dyn Loss>> {
+    let loss: Box<dyn Loss> = match name.split_once(':') {
+        Some(("focal", gamma)) => Box::new(Focal {
+            gamma: gamma
+                .parse::<f64>()
+                .ok()
+                .filter(|g| g.is_finite() && *g >= 0.0)?,
+        }),
+        Some(_) => return None,
+        None => match name {
+            "cross-entropy" => Box::new(CrossEntropy),
+            "binary-cross-entropy" => Box::new(BinaryCrossEntropy),
+            "mse" => Box::new(MeanSquaredError),
+            "hinge" => Box::new(Hinge),
+            "focal" => Box::new(Focal { gamma: 2.0 }),
+            _ => return None,
+        },
+    };
+    Some(loss)
+}
+
+/*Keeps an output away from 0 and 1*/
+fn clamp(a: f64) -> f64 {
+    a.clamp(EPSILON, 1.0 - EPSILON)
+}
+
+/**Categorical cross-entropy, -sum(y * ln(a)), for outputs that are the probabilities
+ * of each class, such as those of softmax */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrossEntropy;
+
+impl Loss for CrossEntropy {
+    fn value(&self, out: &Array2<f64>, lab: &Array2<f64>) -> f64 {
+        out.iter().zip(lab).map(|(a, y)| -y * clamp(*a).ln()).sum()
+    }
+    fn gradient(&self, out: &Array2<f64>, lab: &Array2<f64>) -> Array2<f64> {
+        -lab / &out.mapv(clamp)
+    }
+    fn name(&self) -> String {
+        String::from("cross-entropy")
+    }
+    fn output_error(
+        &self,
+        activation: OutputActivation,
+        out: &Array2<f64>,
+        lab: &Array2<f64>,
+    ) -> Array2<f64> {
+        match activation {
+            //the divisions by the outputs cancel out, so a saturated softmax that is wrong
+            //still gets the full error (for labels that add up to 1)
+            OutputActivation::Softmax => out - lab,
+            _ => activation.error(out, &self.gradient(out, lab)),
+        }
+    }
+}
+
+/**Binary cross-entropy, -sum(y * ln(a) + (1 - y) * ln(1 - a)), treating every output
+ * as the probability of its own class, such as those of sigmoid */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinaryCrossEntropy;
+
+impl Loss for BinaryCrossEntropy {
+    fn value(&self, out: &Array2<f64>, lab: &Array2<f64>) -> f64 {
+        out.iter()
+            .zip(lab)
+            .map(|(a, y)| {
+                let a = clamp(*a);
+                -(y * a.ln() + (1.0 - y) * (1.0 - a).ln())
+            })
+            .sum()
+    }
+    fn gradient(&self, out: &Array2<f64>, lab: &Array2<f64>) -> Array2<f64> {
+        let a = out.mapv(clamp);
+        (&a - lab) / (&a * (1.0 - &a))
+    }
+    fn name(&self) -> String {
+        String::from("binary-cross-entropy")
+    }
+    fn output_error(
+        &self,
+        activation: OutputActivation,
+        out: &Array2<f64>,
+        lab: &Array2<f64>,
+    ) -> Array2<f64> {
+        match activation {
+            //the derivative of sigmoid cancels the divisions, even for outputs rounded to 0 or 1
+            OutputActivation::Sigmoid => out - lab,
+            _ => activation.error(out, &self.gradient(out, lab)),
+        }
+    }
+}
+
+/**Mean squared error, the mean of (a - y)^2 over the outputs of each sample */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeanSquaredError;
+
+impl Loss for MeanSquaredError {
+    fn value(&self, out: &Array2<f64>, lab: &Array2<f64>) -> f64 {
+        (out - lab).mapv(|d| d * d).sum() / out.nrows() as f64
+    }
+    fn gradient(&self, out: &Array2<f64>, lab: &Array2<f64>) -> Array2<f64> {
+        2.0 * (out - lab) / out.nrows() as f64
+    }
+    fn name(&self) -> String {
+        String::from("mse")
+    }
+}
+
+/**Multi-class hinge loss, sum(max(0, 1 + a_j - a_y)) over the wrong classes j,
+ * which wants the true class's output to beat every other output by a margin of 1 */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hinge;
+
+impl Hinge {
+    /*How far each wrong output is within the margin of its sample's true class output,
+     * which is 0 for outputs that are not*/
+    fn margins(out: &Array2<f64>, lab: &Array2<f64>) -> Array2<f64> {
+        let truth = (out * lab).sum_axis(Axis(0));
+        let mut margins = out + 1.0 - &truth;
+        Zip::from(&mut margins)
+            .and(lab)
+            .for_each(|m, &y| *m = if y == 0.0 { m.max(0.0) } else { 0.0 });
+        margins
+    }
+}
+
+impl Loss for Hinge {
+    fn value(&self, out: &Array2<f64>, lab: &Array2<f64>) -> f64 {
+        Self::margins(out, lab).sum()
+    }
+    fn gradient(&self, out: &Array2<f64>, lab: &Array2<f64>) -> Array2<f64> {
+        let violated = Self::margins(out, lab).mapv(|m| if m > 0.0 { 1.0 } else { 0.0 });
+        //each violation pushes its class down and the true class of its sample up
+        let count = violated.sum_axis(Axis(0));
+        &violated - &(lab * &count)
+    }
+    fn name(&self) -> String {
+        String::from("hinge")
+    }
+}
+
+/**Focal loss, -sum(y * (1 - a)^gamma * ln(a)), a cross-entropy that puts less weight on
+ * samples which are already classified confidently, so training focuses on the hard ones */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Focal {
+    pub gamma: f64,
+}
+
+impl Loss for Focal {
+    fn value(&self, out: &Array2<f64>, lab: &Array2<f64>) -> f64 {
+        out.iter()
+            .zip(lab)
+            .map(|(a, y)| {
+                let a = clamp(*a);
+                -y * (1.0 - a).powf(self.gamma) * a.ln()
+            })
+            .sum()
+    }
+    fn gradient(&self, out: &Array2<f64>, lab: &Array2<f64>) -> Array2<f64> {
+        Zip::from(out).and(lab).map_collect(|&a, &y| {
+            let a = clamp(a);
+            y * (self.gamma * (1.0 - a).powf(self.gamma - 1.0) * a.ln()
+                - (1.0 - a).powf(self.gamma) / a)
+        })
+    }
+    fn name(&self) -> String {
+        format!("focal:{}", self.gamma)
+    }
+}
@@ -0,0 +1,265 @@
+use ndarray::{Array, Array2};
+use ndarray_rand::rand::rngs::StdRng;
+use ndarray_rand::rand::{thread_rng, Rng, SeedableRng};
+
+use crate::dataset::Dataset;
+use crate::networks::activation::{Activation, OutputActivation, Sigmoid};
+use crate::networks::loss::{BinaryCrossEntropy, Loss};
+use crate::networks::neural_network::*;
+use crate::networks::optimizer::{Optimizer, Sgd};
+use crate::normalize::Normalizer;
+
+/**A multi-layer perceptron with any number of hidden layers of any width, each with its own
+ * activation function, and an output layer using sigmoid unless another output activation
+ * is set. The hidden layer of the NeuralNetwork getters and setters is the last hidden layer,
+ * the one connected to the output layer. */
+pub struct Mlp {
+    //number of units in each layer, starting with the inputs
+    sizes: Vec<usize>,
+    //the matrices of each layer after the inputs, the last one being the output layer
+    weights: Vec<Array2<f64>>,
+    biases: Vec<Array2<f64>>,
+    layers: Vec<Array2<f64>>,
+    //the weighted sums of each layer, before its activation function is applied
+    sums: Vec<Array2<f64>>,
+    //the activation function of each hidden layer, and of the output layer
+    activations: Vec<Box<dyn Activation>>,
+    output: OutputActivation,
+    //preprocessing of the inputs
+    normalizer: Option<Normalizer>,
+    //the loss function minimized by training
+    loss: Box<dyn Loss>,
+    //updates the weights and biases from their gradients
+    optimizer: Box<dyn Optimizer>,
+}
+
+impl Mlp {
+    /**Initializes a network from the size of each layer, eg. [784, 256, 128, 10]
+     * for 784 inputs, hidden layers of 256 and 128 and 10 outputs, using sigmoid for every layer.
+     * There must be at least one hidden layer. */
+    pub fn new(sizes: &[usize]) -> Self {
+        let activations = (2..sizes.len())
+            .map(|_| Box::new(Sigmoid) as Box<dyn Activation>)
+            .collect();
+        Self::with_activations(sizes, activations)
+    }
+
+    /**Initializes a network from the size of each layer and the activation function
+     * of each hidden layer */
+    pub fn with_activations(sizes: &[usize], activations: Vec<Box<dyn Activation>>) -> Self {
+        Self::with_rng(sizes, activations, &mut thread_rng())
+    }
+
+    /**Initializes a network like with_activations, whose weights are the same for the same seed */
+    pub fn with_seed(sizes: &[usize], activations: Vec<Box<dyn Activation>>, seed: u64) -> Self {
+        Self::with_rng(sizes, activations, &mut StdRng::seed_from_u64(seed))
+    }
+
+    /*Initializes a network, drawing its random weights from rng*/
+    fn with_rng(
+        sizes: &[usize],
+        activations: Vec<Box<dyn Activation>>,
+        rng: &mut impl Rng,
+    ) -> Self {
+        assert!(
+            sizes.len() >= 3,
+            "a network needs inputs, at least one hidden layer and outputs"
+        );
+        assert_eq!(
+            activations.len(),
+            sizes.len() - 2,
+            "every hidden layer needs an activation function"
+        );
+        let pairs = sizes.windows(2);
+        let output = OutputActivation::Sigmoid;
+        //each layer's weights are initialized to suit its activation function
+        let initializers = activations
+            .iter()
+            .map(|a| a.initializer())
+            .chain([output.initializer()]);
+        Self {
+            sizes: sizes.to_vec(),
+            //initial values of weights are randomized
+            weights: pairs
+                .clone()
+                .zip(initializers)
+                .map(|(p, init)| init.init((p[1], p[0]), rng))
+                .collect(),
+            biases: pairs.map(|p| Array::zeros((p[1], 1))).collect(),
+            //the layers themselves begin empty
+            layers: vec![Array::default((0, 0)); sizes.len() - 1],
+            sums: vec![Array::default((0, 0)); sizes.len() - 1],
+            activations,
+            output,
+            //inputs are used as they are until a normalizer is set
+            normalizer: None,
+            loss: Box::new(BinaryCrossEntropy),
+            optimizer: Box::new(Sgd),
+        }
+    }
+
+    /**Initializes a network sized to the inputs and classes of a data set
+     * hidden - The size of each hidden layer
+     */
+    pub fn for_dataset(data: &impl Dataset, hidden: &[usize]) -> Self {
+        let mut sizes = vec![data.input_dim()];
+        sizes.extend_from_slice(hidden);
+        sizes.push(data.num_classes());
+        Self::new(&sizes)
+    }
+
+    /**Returns the size of each layer, starting with the inputs */
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    /**Returns the activation function of each hidden layer */
+    pub fn activations(&self) -> &[Box<dyn Activation>] {
+        &self.activations
+    }
+
+    //index of the last hidden layer in weights, biases and layers
+    fn last_hidden(&self) -> usize {
+        self.layers.len() - 2
+    }
+}
+
+/**Implementing the neural net trait for a network with any number of layers */
+impl NeuralNetwork for Mlp {
+    /**Implements forward propagation through every layer in turn */
+    fn forward_propagation(&mut self, img: &Array2<f64>) {
+        let mut input = img.to_owned();
+        for i in 0..self.layers.len() {
+            self.sums[i] = self.weights[i].dot(&input) + &self.biases[i];
+            input = match self.activations.get(i) {
+                Some(activation) => activation.forward(&self.sums[i]),
+                None => self.output.forward(&self.sums[i]),
+            };
+            self.layers[i] = input.clone();
+        }
+    }
+
+    /**The weights and bias of each layer in turn, from the first hidden layer to the output layer */
+    fn parameters(&self) -> Vec<Array2<f64>> {
+        self.weights
+            .iter()
+            .zip(&self.biases)
+            .flat_map(|(w, b)| [w.to_owned(), b.to_owned()])
+            .collect()
+    }
+
+    fn set_parameters(&mut self, params: Vec<Array2<f64>>) {
+        let mut params = params.into_iter();
+        for (w, b) in self.weights.iter_mut().zip(&mut self.biases) {
+            *w = params.next().expect("a value for every parameter");
+            *b = params.next().expect("a value for every parameter");
+        }
+    }
+
+    /**Computes the gradients working from the output layer back to the first */
+    fn gradients(&self, img: &Array2<f64>, lab: &Array2<f64>) -> Vec<Array2<f64>> {
+        let n = img.ncols() as f64;
+        //the output error uses the derivative of the loss function
+        let mut delta = self.output_error(lab);
+        let mut grads = Vec::new();
+        for i in (0..self.layers.len()).rev() {
+            let input = if i == 0 { img } else { &self.layers[i - 1] };
+            //in reverse, since the gradients are found from the last layer to the first
+            grads.push(bias_gradient(&delta) / n);
+            grads.push(delta.dot(&input.t()) / n);
+            if i > 0 {
+                delta = self.weights[i].t().dot(&delta)
+                    * self.activations[i - 1].derivative(&self.sums[i - 1]);
+            }
+        }
+        grads.reverse();
+        grads
+    }
+
+    /**Setter and getter functions, for the last hidden layer and the output layer */
+    //Getters
+    fn get_hid_layer(&self) -> Array2<f64> {
+        self.layers[self.last_hidden()].to_owned()
+    }
+    fn get_hid_sum(&self) -> Array2<f64> {
+        self.sums[self.last_hidden()].to_owned()
+    }
+    fn get_hid_weights(&self) -> Array2<f64> {
+        self.weights[self.last_hidden()].to_owned()
+    }
+    fn get_hid_bias(&self) -> Array2<f64> {
+        self.biases[self.last_hidden()].to_owned()
+    }
+    fn get_out_layer(&self) -> Array2<f64> {
+        self.layers[self.layers.len() - 1].to_owned()
+    }
+    fn get_out_weights(&self) -> Array2<f64> {
+        self.weights[self.weights.len() - 1].to_owned()
+    }
+    fn get_out_bias(&self) -> Array2<f64> {
+        self.biases[self.biases.len() - 1].to_owned()
+    }
+    fn get_hid_activation(&self) -> &dyn Activation {
+        self.activations[self.last_hidden()].as_ref()
+    }
+    fn get_normalizer(&self) -> Option<&Normalizer> {
+        self.normalizer.as_ref()
+    }
+    fn get_output_activation(&self) -> OutputActivation {
+        self.output
+    }
+    fn get_loss(&self) -> &dyn Loss {
+        self.loss.as_ref()
+    }
+    fn get_optimizer(&self) -> &dyn Optimizer {
+        self.optimizer.as_ref()
+    }
+    fn get_optimizer_mut(&mut self) -> &mut dyn Optimizer {
+        self.optimizer.as_mut()
+    }
+    //Setters
+    fn set_hid_layer(&mut self, x: Array2<f64>) {
+        let i = self.last_hidden();
+        self.layers[i] = x;
+    }
+    fn set_hid_sum(&mut self, x: Array2<f64>) {
+        let i = self.last_hidden();
+        self.sums[i] = x;
+    }
+    fn set_hid_weights(&mut self, x: Array2<f64>) {
+        let i = self.last_hidden();
+        self.weights[i] = x;
+    }
+    fn set_hid_bias(&mut self, x: Array2<f64>) {
+        let i = self.last_hidden();
+        self.biases[i] = x;
+    }
+    fn set_out_layer(&mut self, x: Array2<f64>) {
+        let i = self.layers.len() - 1;
+        self.layers[i] = x;
+    }
+    fn set_out_weights(&mut self, x: Array2<f64>) {
+        let i = self.weights.len() - 1;
+        self.weights[i] = x;
+    }
+    fn set_out_bias(&mut self, x: Array2<f64>) {
+        let i = self.biases.len() - 1;
+        self.biases[i] = x;
+    }
+    fn set_normalizer(&mut self, x: Option<Normalizer>) {
+        self.normalizer = x;
+    }
+    fn set_hid_activation(&mut self, x: Box<dyn Activation>) {
+        let i = self.last_hidden();
+        self.activations[i] = x;
+    }
+    fn set_output_activation(&mut self, x: OutputActivation) {
+        self.output = x;
+    }
+    fn set_loss(&mut self, x: Box<dyn Loss>) {
+        self.loss = x;
+    }
+    fn set_optimizer(&mut self, x: Box<dyn Optimizer>) {
+        self.optimizer = x;
+    }
+}
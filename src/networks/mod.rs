@@ -1,4 +1,10 @@
+pub mod activation;
+pub mod init;
+pub mod loss;
+pub mod mlp;
 pub mod neural_network;
+pub mod optimizer;
 pub mod relu_network;
+pub mod schedule;
 pub mod sigmoid_network;
 pub mod tanh_network;
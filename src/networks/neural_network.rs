@@ -1,4 +1,36 @@
-use ndarray::{Array1, Array2, Zip};
+use ndarray::{stack, Array1, Array2, ArrayView1, Axis, Zip};
+use ndarray_rand::rand::rngs::StdRng;
+use ndarray_rand::rand::seq::SliceRandom;
+use ndarray_rand::rand::{Rng, SeedableRng};
+
+use crate::augment::Augmentation;
+use crate::dataset::Dataset;
+use crate::networks::activation::{Activation, OutputActivation};
+use crate::networks::init::Initializer;
+use crate::networks::loss::Loss;
+use crate::networks::optimizer::Optimizer;
+use crate::networks::schedule::Schedule;
+use crate::normalize::Normalizer;
+
+//number of units in the hidden layer when a network is sized from a data set
+pub const HIDDEN_SIZE: usize = 20;
+
+/**Optional settings for gradient descent, the default keeps the samples in order
+ * and reports only the training accuracy */
+#[derive(Clone, Copy, Default)]
+pub struct TrainOptions<'a> {
+    /**Shuffles the samples at the start of every epoch, the same way for the same seed */
+    pub shuffle_seed: Option<u64>,
+    /**Held-out images and their one hot labels, whose accuracy is reported after every epoch */
+    pub validation: Option<(&'a Array2<f64>, &'a Array2<f64>)>,
+    /**Randomly changes every training image each time it is used */
+    pub augmentation: Option<&'a Augmentation>,
+    /**Trains on mini-batches of this many samples at once, averaging their gradients,
+     * instead of on one sample at a time */
+    pub batch_size: Option<usize>,
+    /**Changes the learning rate every epoch, starting from the one training is given */
+    pub schedule: Option<&'a dyn Schedule>,
+}
 
 /**A trait in rust is similar to interfaces in java.
  * This trait describes a base for the neural network struct */
@@ -6,95 +38,308 @@ pub trait NeuralNetwork {
     /**Getter functions */
     //hidden & output layers
     fn get_hid_layer(&self) -> Array2<f64>;
+    //the weighted sums of the hidden layer, before its activation function is applied
+    fn get_hid_sum(&self) -> Array2<f64>;
     fn get_hid_weights(&self) -> Array2<f64>;
     fn get_hid_bias(&self) -> Array2<f64>;
     fn get_out_layer(&self) -> Array2<f64>;
     fn get_out_weights(&self) -> Array2<f64>;
     fn get_out_bias(&self) -> Array2<f64>;
+    //the activation function of the hidden layer
+    fn get_hid_activation(&self) -> &dyn Activation;
+    //the input normalization the network is trained with, if any
+    fn get_normalizer(&self) -> Option<&Normalizer>;
+    //the activation function of the output layer
+    fn get_output_activation(&self) -> OutputActivation;
+    //the loss function minimized by training
+    fn get_loss(&self) -> &dyn Loss;
+    //the optimizer updating the weights and biases, which keeps its own state between updates
+    fn get_optimizer(&self) -> &dyn Optimizer;
+    fn get_optimizer_mut(&mut self) -> &mut dyn Optimizer;
 
     /**Setter functions */
     fn set_hid_layer(&mut self, x: Array2<f64>);
+    fn set_hid_sum(&mut self, x: Array2<f64>);
     fn set_hid_weights(&mut self, x: Array2<f64>);
     fn set_hid_bias(&mut self, x: Array2<f64>);
     fn set_out_layer(&mut self, x: Array2<f64>);
     fn set_out_weights(&mut self, x: Array2<f64>);
     fn set_out_bias(&mut self, x: Array2<f64>);
+    fn set_normalizer(&mut self, x: Option<Normalizer>);
+    fn set_hid_activation(&mut self, x: Box<dyn Activation>);
+    fn set_output_activation(&mut self, x: OutputActivation);
+    fn set_loss(&mut self, x: Box<dyn Loss>);
+    fn set_optimizer(&mut self, x: Box<dyn Optimizer>);
 
-    /**Activation function and its derivative */
-    fn activation(x: Array2<f64>) -> Array2<f64>;
-    fn activ_deriv(x: &Array2<f64>) -> Array2<f64>;
+    /**The output error, the gradient of the loss with respect to the output layer's
+     * weighted sums, for the labels of the samples held in the output layer */
+    fn output_error(&self, lab: &Array2<f64>) -> Array2<f64> {
+        self.get_loss().output_error(
+            self.get_output_activation(),
+            &Self::get_out_layer(self),
+            lab,
+        )
+    }
 
     /**Optimizes the network, using input matrices x and y, the number of iterations,
      * and the learning rate. */
     fn gradient_descent(&mut self, x: &Array2<f64>, y: &Array2<f64>, epochs: i32, learn_rate: f64) {
-        //counter var for number of correct outputs
+        self.gradient_descent_with(x, y, epochs, learn_rate, &TrainOptions::default());
+    }
+
+    /**Optimizes the network like gradient_descent, with the extra settings in options */
+    fn gradient_descent_with(
+        &mut self,
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        epochs: i32,
+        learn_rate: f64,
+        options: &TrainOptions,
+    ) {
+        let sample = |j: usize| (x.row(j).to_owned(), y.row(j).to_owned());
+        self.run_epochs(x.dim().0, y.dim().1, sample, epochs, learn_rate, options);
+    }
+
+    /**The training loop shared by gradient_descent and train, which retrieves each of
+     * the samples' image and encoded label through the sample function when it is needed */
+    fn run_epochs(
+        &mut self,
+        samples: usize,
+        classes: usize,
+        sample: impl Fn(usize) -> (Array1<f64>, Array1<f64>),
+        epochs: i32,
+        learn_rate: f64,
+        options: &TrainOptions,
+    ) {
+        //the labels must be encoded with one column for each output
+        assert_eq!(
+            classes,
+            self.get_out_bias().dim().0,
+            "labels are encoded with {} classes but the network has {} outputs",
+            classes,
+            self.get_out_bias().dim().0
+        );
+        //the order the samples are visited in, shuffled every epoch if requested
+        let mut order: Vec<usize> = (0..samples).collect();
+        let mut rng = options.shuffle_seed.map(StdRng::seed_from_u64);
+        let mut augment_rng = options.augmentation.map(|a| StdRng::seed_from_u64(a.seed));
+        let batch_size = options.batch_size.unwrap_or(1).max(1);
+        //counter var for number of correct outputs, and the sum of the loss over the epoch
         let mut correct = 0;
+        let mut total_loss = 0.0;
+        //the loss after each epoch, which the schedule may adapt to
+        let mut losses = Vec::new();
         //for each epoch
         for i in 0..epochs {
-            Zip::from(x.rows()).and(y.rows()).for_each(|image, label| {
-                //acquiring each image & label from x & y,
-                //then changing their shape for later calculations
-                let img = image.into_shape((image.len(), 1)).unwrap().to_owned();
-                let lab = label.into_shape((label.len(), 1)).unwrap().to_owned();
+            if let Some(rng) = rng.as_mut() {
+                order.shuffle(rng);
+            }
+            let lr = match options.schedule {
+                Some(schedule) => {
+                    schedule.learn_rate(i as usize, epochs as usize, learn_rate, &losses)
+                }
+                None => learn_rate,
+            };
+            for batch in order.chunks(batch_size) {
+                //acquiring each image & label of the batch,
+                //then placing them side by side as the columns of a matrix
+                let (mut images, mut labels) = (Vec::new(), Vec::new());
+                for &j in batch {
+                    let (mut image, label) = sample(j);
+                    if let (Some(augmentation), Some(rng)) =
+                        (options.augmentation, augment_rng.as_mut())
+                    {
+                        image = augmentation.apply(&image, rng);
+                    }
+                    images.push(self.normalize_input(image));
+                    labels.push(label);
+                }
+                let img = columns(&images);
+                let lab = columns(&labels);
                 //forward propogation assigns values to each matrix
                 self.forward_propagation(&img);
 
-                //error calculation, if the current output is correct
-                correct += if_correct(&Self::get_out_layer(&self), &lab) as u32;
+                //error calculation, how many of the current outputs are correct
+                correct += count_correct(&Self::get_out_layer(self), &lab);
+                total_loss += self.get_loss().value(&Self::get_out_layer(self), &lab);
 
                 //back propagating, modifies each matrix
-                self.back_propagation(&img, &lab, learn_rate);
-            });
+                self.back_propagation(&img, &lab, lr);
+            }
             println!("After Epoch {}:", i + 1);
             //print accuracy
-            let acc = get_percentage(correct, x.dim().0);
+            let acc = get_percentage(correct, samples);
             println!("Accuracy: {:.3}%", acc);
-            //resetting correct counter
+            let mut loss = total_loss / samples as f64;
+            println!("Loss: {:.4}", loss);
+            if let Some((val_x, val_y)) = options.validation {
+                println!("Validation Accuracy: {:.3}%", self.accuracy(val_x, val_y));
+                loss = self.loss(val_x, val_y);
+                println!("Validation Loss: {:.4}", loss);
+            }
+            println!("Learning Rate: {:.6}", lr);
+            losses.push(loss);
+            //resetting the counters
             correct = 0;
+            total_loss = 0.0;
         }
     }
 
+    /**Returns the percentage of images in x the network classifies correctly,
+     * without training on them */
+    fn accuracy(&mut self, x: &Array2<f64>, y: &Array2<f64>) -> f64 {
+        let mut correct = 0;
+        Zip::from(x.rows()).and(y.rows()).for_each(|image, label| {
+            let img = self.normalize_input(image.to_owned()).insert_axis(Axis(1));
+            let lab = label.into_shape((label.len(), 1)).unwrap().to_owned();
+            self.forward_propagation(&img);
+            correct += if_correct(&Self::get_out_layer(self), &lab) as u32;
+        });
+        get_percentage(correct, x.dim().0)
+    }
+
+    /**Returns the mean loss of the network over the images in x, without training on them */
+    fn loss(&mut self, x: &Array2<f64>, y: &Array2<f64>) -> f64 {
+        let mut total = 0.0;
+        Zip::from(x.rows()).and(y.rows()).for_each(|image, label| {
+            let img = self.normalize_input(image.to_owned()).insert_axis(Axis(1));
+            let lab = label.into_shape((label.len(), 1)).unwrap().to_owned();
+            self.forward_propagation(&img);
+            total += self.get_loss().value(&Self::get_out_layer(self), &lab);
+        });
+        total / x.dim().0 as f64
+    }
+
+    /**Optimizes the network on every sample of a data set */
+    fn train(&mut self, data: &impl Dataset, epochs: i32, learn_rate: f64) {
+        self.train_with(data, epochs, learn_rate, &TrainOptions::default());
+    }
+
+    /**Optimizes the network on a data set like train, with the extra settings in options.
+     * Samples are retrieved one at a time, so the data set is never converted to floats at once */
+    fn train_with(
+        &mut self,
+        data: &impl Dataset,
+        epochs: i32,
+        learn_rate: f64,
+        options: &TrainOptions,
+    ) {
+        let classes = data.num_classes();
+        let sample = |j: usize| {
+            let (image, class) = data.get(j);
+            let mut label = Array1::zeros(classes);
+            label[class] = 1.0;
+            (image, label)
+        };
+        self.run_epochs(data.len(), classes, sample, epochs, learn_rate, options);
+    }
+
     /**Implements forward propagation using an input matrix */
     fn forward_propagation(&mut self, img: &Array2<f64>) {
         //calculating the hidden layer matrix in h_calc
-        let hid_calc = Self::get_hid_weights(&self).dot(img) + &Self::get_hid_bias(&self);
-        //applying the activation function sigmoid
-        Self::set_hid_layer(self, Self::activation(hid_calc));
+        let hid_calc = Self::get_hid_weights(self).dot(img) + &Self::get_hid_bias(self);
+        //applying the hidden layer's activation function, keeping the sums for its derivative
+        Self::set_hid_layer(self, self.get_hid_activation().forward(&hid_calc));
+        Self::set_hid_sum(self, hid_calc);
 
         //calculating the output layer matrix
-        let out_calc = Self::get_out_weights(&self).dot(&Self::get_hid_layer(&self))
-            + &Self::get_out_bias(&self);
-        //applying activation function
-        Self::set_out_layer(self, Self::activation(out_calc));
+        let out_calc =
+            Self::get_out_weights(self).dot(&Self::get_hid_layer(self)) + &Self::get_out_bias(self);
+        //applying the output layer's own activation function
+        Self::set_out_layer(self, self.get_output_activation().forward(&out_calc));
     }
 
-    /**Implements backwards propagation using the input matrices*/
-    fn back_propagation(&mut self, img: &Array2<f64>, lab: &Array2<f64>, lr: f64) {
-        //Working backwards from the output to the hidden layer
-        //delta_out calculates the output error using the derivative of the cost function
-        let delta_out = &Self::get_out_layer(&self) - lab;
-        Self::set_out_weights(
-            self,
-            &Self::get_out_weights(&self) + (-lr * &delta_out.dot(&Self::get_hid_layer(&self).t())),
-        );
-        Self::set_out_bias(self, &Self::get_out_bias(&self) + (-lr * &delta_out));
-
-        //delta_hid is calculated using the derivative of the activation function.
-        let delta_hid = Self::get_out_weights(&self).t().dot(&delta_out)
-            * Self::activ_deriv(&Self::get_hid_layer(&self));
-        Self::set_hid_weights(
-            self,
-            &Self::get_hid_weights(&self) + (-lr * delta_hid.dot(&img.t())),
+    /**Returns the weights and biases of the network, in the same order as gradients:
+     * the hidden layer's weights and bias, then the output layer's */
+    fn parameters(&self) -> Vec<Array2<f64>> {
+        vec![
+            Self::get_hid_weights(self),
+            Self::get_hid_bias(self),
+            Self::get_out_weights(self),
+            Self::get_out_bias(self),
+        ]
+    }
+
+    /**Replaces the weights and biases of the network, given in the order of parameters */
+    fn set_parameters(&mut self, params: Vec<Array2<f64>>) {
+        let mut params = params.into_iter();
+        let mut next = || params.next().expect("a value for every parameter");
+        Self::set_hid_weights(self, next());
+        Self::set_hid_bias(self, next());
+        Self::set_out_weights(self, next());
+        Self::set_out_bias(self, next());
+    }
+
+    /**Sets the weights of each layer after the inputs with its own initializer,
+     * and every bias with the bias initializer, drawing random values from rng */
+    fn initialize(&mut self, weights: &[Initializer], bias: Initializer, rng: &mut impl Rng) {
+        let params = self.parameters();
+        assert_eq!(
+            weights.len(),
+            params.len() / 2,
+            "every layer needs an initializer for its weights"
         );
-        Self::set_hid_bias(self, Self::get_hid_bias(&self) + (-lr * delta_hid));
+        //the parameters are the weights and bias of each layer in turn
+        let params = params
+            .chunks(2)
+            .zip(weights)
+            .flat_map(|(layer, init)| {
+                [
+                    init.init(layer[0].dim(), rng),
+                    bias.init(layer[1].dim(), rng),
+                ]
+            })
+            .collect();
+        self.set_parameters(params);
+    }
+
+    /**Returns the gradient of the loss, averaged over the samples in the columns of img,
+     * with respect to each parameter. The layers must hold the forward propagation of img */
+    fn gradients(&self, img: &Array2<f64>, lab: &Array2<f64>) -> Vec<Array2<f64>> {
+        let n = img.ncols() as f64;
+        //Working backwards from the output to the hidden layer
+        //delta_out calculates the output error using the derivative of the loss function
+        let delta_out = self.output_error(lab);
+        //delta_hid is calculated using the derivative of the activation function
+        let delta_hid = Self::get_out_weights(self).t().dot(&delta_out)
+            * self
+                .get_hid_activation()
+                .derivative(&Self::get_hid_sum(self));
+        vec![
+            delta_hid.dot(&img.t()) / n,
+            bias_gradient(&delta_hid) / n,
+            delta_out.dot(&Self::get_hid_layer(self).t()) / n,
+            bias_gradient(&delta_out) / n,
+        ]
+    }
+
+    /**Implements backwards propagation using the input matrices, with one sample per column.
+     * The gradients are averaged over the samples, then the optimizer updates every parameter */
+    fn back_propagation(&mut self, img: &Array2<f64>, lab: &Array2<f64>, lr: f64) {
+        let grads = self.gradients(img, lab);
+        let mut params = self.parameters();
+        let optimizer = self.get_optimizer_mut();
+        for (i, (param, grad)) in params.iter_mut().zip(&grads).enumerate() {
+            optimizer.update(i, param, grad, lr);
+        }
+        self.set_parameters(params);
+    }
+
+    /**Applies the network's normalizer to an input, so that every input is preprocessed
+     * with the statistics the network was trained with */
+    fn normalize_input(&self, image: Array1<f64>) -> Array1<f64> {
+        match self.get_normalizer() {
+            Some(normalizer) => normalizer.apply(&image),
+            None => image,
+        }
     }
 
     /**The network will guess what digit the image is */
     fn make_guess(&mut self, image: &Array1<f64>) -> usize {
         //to make a guess, the network must forward propogate once with the img
-        let img = image.to_shape((image.len(), 1)).unwrap().to_owned();
+        let img = self.normalize_input(image.to_owned()).insert_axis(Axis(1));
         self.forward_propagation(&img);
-        argmax(&Self::get_out_layer(&self).column(0).to_owned())
+        argmax(&Self::get_out_layer(self).column(0).to_owned())
     }
 }
 
@@ -103,6 +348,26 @@ pub fn if_correct(a: &Array2<f64>, b: &Array2<f64>) -> bool {
     argmax(&a.column(0).to_owned()) == argmax(&b.column(0).to_owned())
 }
 
+/**Counts the columns of a whose most likely prediction is the class encoded in b */
+pub fn count_correct(a: &Array2<f64>, b: &Array2<f64>) -> u32 {
+    Zip::from(a.columns())
+        .and(b.columns())
+        .fold(0, |count, a, b| {
+            count + (argmax(&a.to_owned()) == argmax(&b.to_owned())) as u32
+        })
+}
+
+/**The gradient of a layer's bias, the sum of the layer's error over the samples */
+pub fn bias_gradient(delta: &Array2<f64>) -> Array2<f64> {
+    delta.sum_axis(Axis(1)).insert_axis(Axis(1))
+}
+
+/*Places vectors of the same length side by side as the columns of a matrix*/
+fn columns(vectors: &[Array1<f64>]) -> Array2<f64> {
+    let views: Vec<ArrayView1<f64>> = vectors.iter().map(|v| v.view()).collect();
+    stack(Axis(1), &views).unwrap()
+}
+
 /**returns the percentage of correct guesses during training */
 pub fn get_percentage(correct: u32, total: usize) -> f64 {
     correct as f64 / total as f64 * 100.0
@@ -0,0 +1,263 @@
+use ndarray::Array2;
+
+//added to the denominators of the adaptive optimizers so they never divide by 0
+const EPSILON: f64 = 1e-8;
+
+/**Updates the parameters of a network from their gradients. Optimizers keep their own state
+ * for each parameter (eg. a running average of its gradients) between updates. */
+pub trait Optimizer {
+    /**Moves one parameter against its gradient.
+     * index - Which parameter of the network this is, the same every time it is updated
+     * lr - The learning rate
+     */
+    fn update(&mut self, index: usize, param: &mut Array2<f64>, grad: &Array2<f64>, lr: f64);
+    /**The name the optimizer is chosen by in optimizer_from_name */
+    fn name(&self) -> String;
+}
+
+/**Returns the optimizer with the given name: sgd, momentum, nesterov, rmsprop, adagrad, adam
+ * or adamw, using the usual defaults for their settings. The momentum of momentum and nesterov
+ * (default 0.9), the decay of rmsprop (default 0.9) and the weight decay of adamw
+ * (default 0.01) can follow the name after a colon, eg. momentum:0.95. The momentum and
+ * the decay must be at least 0 and below 1, and the weight decay must not be negative */
+pub fn optimizer_from_name(name: &str) -> Option<Box<dyn Optimizer>> {
+    let (name, param) = match name.split_once(':') {
+        Some((name, param)) => (
+            name,
+            Some(param.parse::<f64>().ok().filter(|p| p.is_finite())?),
+        ),
+        None => (name, None),
+    };
+    let optimizer: Box<dyn Optimizer> = match (name, param) {
+        //the fraction of the previous average kept at every update
+        ("momentum" | "nesterov" | "rmsprop", Some(beta)) if !(0.0..1.0).contains(&beta) => {
+            return None
+        }
+        ("adamw", Some(decay)) if decay < 0.0 => return None,
+        ("momentum", beta) => Box::new(Momentum::new(beta.unwrap_or(0.9))),
+        ("nesterov", beta) => Box::new(Nesterov::new(beta.unwrap_or(0.9))),
+        ("rmsprop", decay) => Box::new(RmsProp::new(decay.unwrap_or(0.9))),
+        ("adamw", decay) => Box::new(AdamW::new(decay.unwrap_or(0.01))),
+        (_, Some(_)) => return None,
+        ("sgd", None) => Box::new(Sgd),
+        ("adagrad", None) => Box::new(AdaGrad::default()),
+        ("adam", None) => Box::new(Adam::default()),
+        _ => return None,
+    };
+    Some(optimizer)
+}
+
+/*The state kept for a parameter, which starts at zeros the first time it is updated*/
+fn state<'a>(
+    states: &'a mut Vec<Array2<f64>>,
+    index: usize,
+    param: &Array2<f64>,
+) -> &'a mut Array2<f64> {
+    if states.len() <= index {
+        states.resize(index + 1, Array2::zeros((0, 0)));
+    }
+    if states[index].dim() != param.dim() {
+        states[index] = Array2::zeros(param.raw_dim());
+    }
+    &mut states[index]
+}
+
+/**Plain stochastic gradient descent, param - lr * grad */
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Sgd;
+
+impl Optimizer for Sgd {
+    fn update(&mut self, _: usize, param: &mut Array2<f64>, grad: &Array2<f64>, lr: f64) {
+        param.scaled_add(-lr, grad);
+    }
+    fn name(&self) -> String {
+        String::from("sgd")
+    }
+}
+
+/**Gradient descent with momentum, which moves along a running sum of the gradients
+ * that decays by beta every update */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Momentum {
+    pub beta: f64,
+    velocity: Vec<Array2<f64>>,
+}
+
+impl Momentum {
+    /**Initializes the optimizer with no state */
+    pub fn new(beta: f64) -> Self {
+        Self {
+            beta,
+            velocity: Vec::new(),
+        }
+    }
+}
+
+impl Optimizer for Momentum {
+    fn update(&mut self, index: usize, param: &mut Array2<f64>, grad: &Array2<f64>, lr: f64) {
+        let velocity = state(&mut self.velocity, index, param);
+        *velocity = &*velocity * self.beta + grad;
+        param.scaled_add(-lr, velocity);
+    }
+    fn name(&self) -> String {
+        format!("momentum:{}", self.beta)
+    }
+}
+
+/**Nesterov accelerated gradient, momentum that looks ahead by also adding
+ * the gradient to the velocity it is about to move along */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Nesterov {
+    pub beta: f64,
+    velocity: Vec<Array2<f64>>,
+}
+
+impl Nesterov {
+    /**Initializes the optimizer with no state */
+    pub fn new(beta: f64) -> Self {
+        Self {
+            beta,
+            velocity: Vec::new(),
+        }
+    }
+}
+
+impl Optimizer for Nesterov {
+    fn update(&mut self, index: usize, param: &mut Array2<f64>, grad: &Array2<f64>, lr: f64) {
+        let velocity = state(&mut self.velocity, index, param);
+        *velocity = &*velocity * self.beta + grad;
+        param.scaled_add(-lr, &(grad + &(&*velocity * self.beta)));
+    }
+    fn name(&self) -> String {
+        format!("nesterov:{}", self.beta)
+    }
+}
+
+/**RMSProp, which divides each gradient by a running average of its recent magnitude,
+ * so every weight moves at a similar pace */
+#[derive(Debug, Clone, PartialEq)]
+pub struct RmsProp {
+    pub decay: f64,
+    squares: Vec<Array2<f64>>,
+}
+
+impl RmsProp {
+    /**Initializes the optimizer with no state */
+    pub fn new(decay: f64) -> Self {
+        Self {
+            decay,
+            squares: Vec::new(),
+        }
+    }
+}
+
+impl Optimizer for RmsProp {
+    fn update(&mut self, index: usize, param: &mut Array2<f64>, grad: &Array2<f64>, lr: f64) {
+        let squares = state(&mut self.squares, index, param);
+        *squares = &*squares * self.decay + &(grad * grad * (1.0 - self.decay));
+        *param -= &(lr * grad / &squares.mapv(|s| s.sqrt() + EPSILON));
+    }
+    fn name(&self) -> String {
+        format!("rmsprop:{}", self.decay)
+    }
+}
+
+/**AdaGrad, which divides each gradient by the root of the sum of all its squares so far,
+ * so weights that have already moved a lot slow down */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AdaGrad {
+    squares: Vec<Array2<f64>>,
+}
+
+impl Optimizer for AdaGrad {
+    fn update(&mut self, index: usize, param: &mut Array2<f64>, grad: &Array2<f64>, lr: f64) {
+        let squares = state(&mut self.squares, index, param);
+        *squares += &(grad * grad);
+        *param -= &(lr * grad / &squares.mapv(|s| s.sqrt() + EPSILON));
+    }
+    fn name(&self) -> String {
+        String::from("adagrad")
+    }
+}
+
+/**Adam, which moves along a running average of the gradients divided by the root of
+ * a running average of their squares, both corrected for starting at 0 */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Adam {
+    pub beta1: f64,
+    pub beta2: f64,
+    means: Vec<Array2<f64>>,
+    squares: Vec<Array2<f64>>,
+    //how many times each parameter has been updated
+    steps: Vec<i32>,
+}
+
+impl Default for Adam {
+    fn default() -> Self {
+        Self {
+            beta1: 0.9,
+            beta2: 0.999,
+            means: Vec::new(),
+            squares: Vec::new(),
+            steps: Vec::new(),
+        }
+    }
+}
+
+impl Adam {
+    /*Updates the averages of a parameter and returns the step it moves by for a learning rate of 1*/
+    fn step(&mut self, index: usize, param: &Array2<f64>, grad: &Array2<f64>) -> Array2<f64> {
+        if self.steps.len() <= index {
+            self.steps.resize(index + 1, 0);
+        }
+        self.steps[index] += 1;
+        let t = self.steps[index];
+        let (beta1, beta2) = (self.beta1, self.beta2);
+        let mean = state(&mut self.means, index, param);
+        *mean = &*mean * beta1 + &(grad * (1.0 - beta1));
+        let mean = &*mean / (1.0 - beta1.powi(t));
+        let squares = state(&mut self.squares, index, param);
+        *squares = &*squares * beta2 + &(grad * grad * (1.0 - beta2));
+        let squares = &*squares / (1.0 - beta2.powi(t));
+        mean / squares.mapv(|s| s.sqrt() + EPSILON)
+    }
+}
+
+impl Optimizer for Adam {
+    fn update(&mut self, index: usize, param: &mut Array2<f64>, grad: &Array2<f64>, lr: f64) {
+        let step = self.step(index, param, grad);
+        param.scaled_add(-lr, &step);
+    }
+    fn name(&self) -> String {
+        String::from("adam")
+    }
+}
+
+/**Adam with decoupled weight decay, which shrinks every parameter towards 0 in proportion
+ * to the learning rate, separately from the gradients */
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdamW {
+    pub weight_decay: f64,
+    adam: Adam,
+}
+
+impl AdamW {
+    /**Initializes the optimizer with no state */
+    pub fn new(weight_decay: f64) -> Self {
+        Self {
+            weight_decay,
+            adam: Adam::default(),
+        }
+    }
+}
+
+impl Optimizer for AdamW {
+    fn update(&mut self, index: usize, param: &mut Array2<f64>, grad: &Array2<f64>, lr: f64) {
+        let step = self.adam.step(index, param, grad);
+        *param *= 1.0 - lr * self.weight_decay;
+        param.scaled_add(-lr, &step);
+    }
+    fn name(&self) -> String {
+        format!("adamw:{}", self.weight_decay)
+    }
+}
@@ -1,8 +1,16 @@
-use ndarray::{Array, Array2, Axis};
-use ndarray_rand::rand_distr::Uniform;
-use ndarray_rand::RandomExt;
+use ndarray::{Array, Array2};
+use ndarray_rand::rand::rngs::StdRng;
+use ndarray_rand::rand::{thread_rng, Rng, SeedableRng};
 
+use crate::dataset::Dataset;
+use crate::networks::activation::{Activation, LeakyRelu, OutputActivation};
+use crate::networks::loss::{CrossEntropy, Loss};
 use crate::networks::neural_network::*;
+use crate::networks::optimizer::{Optimizer, Sgd};
+use crate::normalize::Normalizer;
+
+//the activation function of the hidden layer
+const LEAKY_RELU: LeakyRelu = LeakyRelu { alpha: 0.01 };
 
 /**Defining the Neural Network*/
 pub struct ReluNetwork {
@@ -16,92 +24,86 @@ pub struct ReluNetwork {
     //layers
     hidden_layer: Array2<f64>,
     output_layer: Array2<f64>,
+    //the weighted sums of the hidden layer, before its activation function is applied
+    hidden_sum: Array2<f64>,
+    //the activation function of the hidden layer
+    activation: Box<dyn Activation>,
+    //preprocessing of the inputs
+    normalizer: Option<Normalizer>,
+    //the activation function of the output layer
+    output: OutputActivation,
+    //the loss function minimized by training
+    loss: Box<dyn Loss>,
+    //updates the weights and biases from their gradients
+    optimizer: Box<dyn Optimizer>,
 }
 
 /**Implementing the unique activation functions for this implementation of the network */
 impl ReluNetwork {
-    /**Constructor-like function that initializes and returns a network */
+    /**Constructor-like function that initializes and returns a network for MNIST,
+     * with 784 inputs, a hidden layer of 20 and 10 outputs */
     pub fn new() -> Self {
+        Self::with_sizes(784, HIDDEN_SIZE, 10)
+    }
+
+    /**Initializes a network sized to the inputs and classes of a data set */
+    pub fn for_dataset(data: &impl Dataset) -> Self {
+        Self::with_sizes(data.input_dim(), HIDDEN_SIZE, data.num_classes())
+    }
+
+    /**Initializes a network with the given number of inputs, hidden units and outputs */
+    pub fn with_sizes(inputs: usize, hidden: usize, outputs: usize) -> Self {
+        Self::with_rng(inputs, hidden, outputs, &mut thread_rng())
+    }
+
+    /**Initializes a network like with_sizes, whose weights are the same for the same seed */
+    pub fn with_seed(inputs: usize, hidden: usize, outputs: usize, seed: u64) -> Self {
+        Self::with_rng(inputs, hidden, outputs, &mut StdRng::seed_from_u64(seed))
+    }
+
+    /*Initializes a network, drawing its random weights from rng*/
+    fn with_rng(inputs: usize, hidden: usize, outputs: usize, rng: &mut impl Rng) -> Self {
         //initializes each layer and its corresponding weights and biases
         //the hidden and output layers themselves begin empty
+        let activation: Box<dyn Activation> = Box::new(LEAKY_RELU);
+        let output = OutputActivation::Softmax;
         Self {
-            //initial values of weights are randomized
+            //initial values of weights are randomized, scaled to suit each layer's activation
             //hidden
             hidden_layer: Array::default((0, 0)),
-            hidden_weights: Array::random((20, 784), Uniform::new(-0.5, 0.5)),
-            hidden_bias: Array::zeros((20, 1)),
+            hidden_sum: Array::default((0, 0)),
+            hidden_weights: activation.initializer().init((hidden, inputs), rng),
+            hidden_bias: Array::zeros((hidden, 1)),
             //output
             output_layer: Array::default((0, 0)),
-            output_weights: Array::random((10, 20), Uniform::new(-0.5, 0.5)),
-            output_bias: Array::zeros((10, 1)),
+            output_weights: output.initializer().init((outputs, hidden), rng),
+            output_bias: Array::zeros((outputs, 1)),
+            //inputs are used as they are until a normalizer is set
+            normalizer: None,
+            activation,
+            output,
+            loss: Box::new(CrossEntropy),
+            optimizer: Box::new(Sgd),
         }
     }
+}
 
-    /**The softmax activation function to be used on the output layer */
-    fn softmax(x: Array2<f64>) -> Array2<f64> {
-        //subtracting by max value to prevent overflow (NaN values)
-        let max = x.column(0).into_iter().fold(f64::MIN, |a, &b| a.max(b));
-        let exp = x.mapv(|v| (v - max).exp());
-        //println!("{}", exp);
-        &exp / exp.sum_axis(Axis(0))
+impl Default for ReluNetwork {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 /**Implementing the neural net trait for the network using Leaky ReLU and Softmax for the activation functions */
 impl NeuralNetwork for ReluNetwork {
-    /**Implementing the activation function as ReLU(x), to be used on the hidden layer */
-    fn activation(x: Array2<f64>) -> Array2<f64> {
-        //Using a variant of ReLU called leaky ReLU,
-        //which returns x or 0.01x
-        x.mapv(|v| if v > 0.0 { v } else { v * 0.01 })
-    }
-
-    /**Implementing as the derivative of ReLU(x) */
-    fn activ_deriv(x: &Array2<f64>) -> Array2<f64> {
-        //Leaky ReLU's derivative is 1 if x is positive, 0.01 otherwise
-        x.mapv(|v| if v > 0.0 { 1.0 } else { 0.01 })
-    }
-
-    /**Overriding the forward propogation function for the use of ReLU and Softmax */
-    fn forward_propagation(&mut self, img: &Array2<f64>) {
-        //calculating the hidden layer matrix in h_calc
-        let hid_calc = Self::get_hid_weights(&self).dot(img) + &Self::get_hid_bias(&self);
-        //applying the activation function sigmoid
-        Self::set_hid_layer(self, Self::activation(hid_calc));
-
-        //calculating the output layer matrix
-        let out_calc = Self::get_out_weights(&self).dot(&Self::get_hid_layer(&self))
-            + &Self::get_out_bias(&self);
-        //applying activation function
-        Self::set_out_layer(self, Self::softmax(out_calc));
-    }
-
-    /**Implements backwards propagation using the input matrices*/
-    fn back_propagation(&mut self, img: &Array2<f64>, lab: &Array2<f64>, lr: f64) {
-        //Working backwards from the output to the hidden layer
-        //delta_out calculates the output error using the derivative of the cost function
-        let delta_out = &Self::get_out_layer(&self) - lab;
-        Self::set_out_weights(
-            self,
-            &Self::get_out_weights(&self) + (-lr * &delta_out.dot(&Self::get_hid_layer(&self).t())),
-        );
-        Self::set_out_bias(self, &Self::get_out_bias(&self) + (-lr * &delta_out));
-
-        //delta_hid is calculated using the derivative of the activation function.
-        let delta_hid = Self::get_out_weights(&self).t().dot(&delta_out)
-            * Self::activ_deriv(&Self::get_hid_layer(&self));
-        Self::set_hid_weights(
-            self,
-            &Self::get_hid_weights(&self) + (-lr * delta_hid.dot(&img.t())),
-        );
-        Self::set_hid_bias(self, Self::get_hid_bias(&self) + (-lr * delta_hid));
-    }
-
     /**Setter and getter functions for each array */
     //Getters
     fn get_hid_layer(&self) -> Array2<f64> {
         self.hidden_layer.to_owned()
     }
+    fn get_hid_sum(&self) -> Array2<f64> {
+        self.hidden_sum.to_owned()
+    }
     fn get_hid_weights(&self) -> Array2<f64> {
         self.hidden_weights.to_owned()
     }
@@ -117,10 +119,31 @@ impl NeuralNetwork for ReluNetwork {
     fn get_out_bias(&self) -> Array2<f64> {
         self.output_bias.to_owned()
     }
+    fn get_hid_activation(&self) -> &dyn Activation {
+        self.activation.as_ref()
+    }
+    fn get_normalizer(&self) -> Option<&Normalizer> {
+        self.normalizer.as_ref()
+    }
+    fn get_output_activation(&self) -> OutputActivation {
+        self.output
+    }
+    fn get_loss(&self) -> &dyn Loss {
+        self.loss.as_ref()
+    }
+    fn get_optimizer(&self) -> &dyn Optimizer {
+        self.optimizer.as_ref()
+    }
+    fn get_optimizer_mut(&mut self) -> &mut dyn Optimizer {
+        self.optimizer.as_mut()
+    }
     //Setters
     fn set_hid_layer(&mut self, x: Array2<f64>) {
         self.hidden_layer = x;
     }
+    fn set_hid_sum(&mut self, x: Array2<f64>) {
+        self.hidden_sum = x;
+    }
     fn set_hid_weights(&mut self, x: Array2<f64>) {
         self.hidden_weights = x;
     }
@@ -136,4 +159,19 @@ impl NeuralNetwork for ReluNetwork {
     fn set_out_bias(&mut self, x: Array2<f64>) {
         self.output_bias = x;
     }
+    fn set_normalizer(&mut self, x: Option<Normalizer>) {
+        self.normalizer = x;
+    }
+    fn set_hid_activation(&mut self, x: Box<dyn Activation>) {
+        self.activation = x;
+    }
+    fn set_output_activation(&mut self, x: OutputActivation) {
+        self.output = x;
+    }
+    fn set_loss(&mut self, x: Box<dyn Loss>) {
+        self.loss = x;
+    }
+    fn set_optimizer(&mut self, x: Box<dyn Optimizer>) {
+        self.optimizer = x;
+    }
 }
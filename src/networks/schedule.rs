@@ -0,0 +1,249 @@
+use std::f64::consts::PI;
+
+//the fraction of training one-cycle spends raising the learning rate
+const ONE_CYCLE_RISE: f64 = 0.3;
+//the base is divided by this to start, and the start by ONE_CYCLE_END_DIV to end
+const ONE_CYCLE_START_DIV: f64 = 25.0;
+const ONE_CYCLE_END_DIV: f64 = 1e4;
+
+/**Changes the learning rate from one epoch to the next. The learning rate of an epoch only
+ * depends on the arguments, so the same schedule can be shared by several networks. */
+pub trait Schedule {
+    /**The learning rate of an epoch.
+     * epoch - The epoch, counting from 0
+     * epochs - How many epochs training lasts
+     * base - The learning rate training was started with
+     * losses - The loss after each epoch so far (the validation loss when there is one)
+     */
+    fn learn_rate(&self, epoch: usize, epochs: usize, base: f64, losses: &[f64]) -> f64;
+    /**The name the schedule is chosen by in schedule_from_name */
+    fn name(&self) -> String;
+}
+
+/**Returns the schedule with the given name: constant, step, exponential, cosine, warmup,
+ * one-cycle or plateau. Their settings can follow the name after colons, eg. step:10:0.5 for
+ * halving every 10 epochs, and warmup can be followed by another schedule after a plus sign,
+ * eg. warmup:2+cosine:10. Numbers of epochs must be whole and at least 1, and the factors of
+ * step, exponential and plateau must be above 0 and at most 1 */
+pub fn schedule_from_name(name: &str) -> Option<Box<dyn Schedule>> {
+    if let Some((warmup, after)) = name.split_once('+') {
+        let Warmup { epochs, .. } = parse_warmup(warmup)?;
+        return Some(Box::new(Warmup {
+            epochs,
+            after: schedule_from_name(after)?,
+        }));
+    }
+    let mut parts = name.split(':');
+    let kind = parts.next()?;
+    let params = parts
+        .map(|p| p.parse::<f64>().ok())
+        .collect::<Option<Vec<f64>>>()?;
+    //the learning rate is multiplied by a factor, which must shrink it without reaching 0
+    let factor = |i: usize, default: f64| match params.get(i) {
+        Some(&p) if p > 0.0 && p <= 1.0 => Some(p),
+        Some(_) => None,
+        None => Some(default),
+    };
+    let count = |i: usize, default: usize| match params.get(i) {
+        Some(&p) if p >= 1.0 && p.fract() == 0.0 => Some(p as usize),
+        Some(_) => None,
+        None => Some(default),
+    };
+    let (schedule, settings): (Box<dyn Schedule>, usize) = match kind {
+        "constant" => (Box::new(Constant), 0),
+        "step" => (
+            Box::new(StepDecay {
+                step: count(0, 10)?,
+                factor: factor(1, 0.5)?,
+            }),
+            2,
+        ),
+        "exponential" => (
+            Box::new(Exponential {
+                gamma: factor(0, 0.9)?,
+            }),
+            1,
+        ),
+        "cosine" => (
+            Box::new(CosineRestarts {
+                period: count(0, 10)?,
+                mult: count(1, 1)?,
+            }),
+            2,
+        ),
+        "warmup" => (Box::new(parse_warmup(name)?), 1),
+        "one-cycle" => (Box::new(OneCycle), 0),
+        "plateau" => (
+            Box::new(Plateau {
+                patience: count(0, 2)?,
+                factor: factor(1, 0.5)?,
+            }),
+            2,
+        ),
+        _ => return None,
+    };
+    (params.len() <= settings).then_some(schedule)
+}
+
+/*Reads warmup or warmup:epochs, followed by a constant learning rate*/
+fn parse_warmup(name: &str) -> Option<Warmup> {
+    let epochs = match name.split_once(':') {
+        Some(("warmup", epochs)) => epochs.parse().ok().filter(|&e| e > 0)?,
+        None if name == "warmup" => 5,
+        _ => return None,
+    };
+    Some(Warmup {
+        epochs,
+        after: Box::new(Constant),
+    })
+}
+
+/**The same learning rate for every epoch */
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Constant;
+
+impl Schedule for Constant {
+    fn learn_rate(&self, _: usize, _: usize, base: f64, _: &[f64]) -> f64 {
+        base
+    }
+    fn name(&self) -> String {
+        String::from("constant")
+    }
+}
+
+/**Multiplies the learning rate by factor every step epochs */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepDecay {
+    pub step: usize,
+    pub factor: f64,
+}
+
+impl Schedule for StepDecay {
+    fn learn_rate(&self, epoch: usize, _: usize, base: f64, _: &[f64]) -> f64 {
+        base * self.factor.powi((epoch / self.step) as i32)
+    }
+    fn name(&self) -> String {
+        format!("step:{}:{}", self.step, self.factor)
+    }
+}
+
+/**Multiplies the learning rate by gamma every epoch */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exponential {
+    pub gamma: f64,
+}
+
+impl Schedule for Exponential {
+    fn learn_rate(&self, epoch: usize, _: usize, base: f64, _: &[f64]) -> f64 {
+        base * self.gamma.powi(epoch as i32)
+    }
+    fn name(&self) -> String {
+        format!("exponential:{}", self.gamma)
+    }
+}
+
+/**Cosine annealing with warm restarts: the learning rate falls from the base towards 0 along
+ * half a cosine over period epochs, then starts again from the base with a period mult
+ * times as long */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CosineRestarts {
+    pub period: usize,
+    pub mult: usize,
+}
+
+impl Schedule for CosineRestarts {
+    fn learn_rate(&self, epoch: usize, _: usize, base: f64, _: &[f64]) -> f64 {
+        //finding how far into its own cycle the epoch is
+        let (mut start, mut period) = (epoch, self.period);
+        while start >= period {
+            start -= period;
+            period *= self.mult;
+        }
+        base * 0.5 * (1.0 + (PI * start as f64 / period as f64).cos())
+    }
+    fn name(&self) -> String {
+        format!("cosine:{}:{}", self.period, self.mult)
+    }
+}
+
+/**Raises the learning rate linearly up to the base over the first epochs, then follows
+ * another schedule as if training had started when the warmup ended */
+pub struct Warmup {
+    pub epochs: usize,
+    pub after: Box<dyn Schedule>,
+}
+
+impl Schedule for Warmup {
+    fn learn_rate(&self, epoch: usize, epochs: usize, base: f64, losses: &[f64]) -> f64 {
+        if epoch < self.epochs {
+            base * (epoch + 1) as f64 / self.epochs as f64
+        } else {
+            let losses = &losses[self.epochs.min(losses.len())..];
+            let remaining = epochs.saturating_sub(self.epochs);
+            self.after
+                .learn_rate(epoch - self.epochs, remaining, base, losses)
+        }
+    }
+    fn name(&self) -> String {
+        match self.after.name().as_str() {
+            "constant" => format!("warmup:{}", self.epochs),
+            after => format!("warmup:{}+{}", self.epochs, after),
+        }
+    }
+}
+
+/**The one-cycle policy: the learning rate rises from a 25th of the base to the base over
+ * the first 30% of training, then anneals far below where it started */
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OneCycle;
+
+impl Schedule for OneCycle {
+    fn learn_rate(&self, epoch: usize, epochs: usize, base: f64, _: &[f64]) -> f64 {
+        let start = base / ONE_CYCLE_START_DIV;
+        let end = start / ONE_CYCLE_END_DIV;
+        //how far through training the epoch is, from 0 for the first epoch to 1 for the last
+        let t = epoch as f64 / (epochs.max(2) - 1) as f64;
+        //half a cosine from one rate to another, as progress goes from 0 to 1
+        let anneal = |from: f64, to: f64, progress: f64| {
+            to + (from - to) * 0.5 * (1.0 + (PI * progress).cos())
+        };
+        if t < ONE_CYCLE_RISE {
+            anneal(start, base, t / ONE_CYCLE_RISE)
+        } else {
+            anneal(base, end, (t - ONE_CYCLE_RISE) / (1.0 - ONE_CYCLE_RISE))
+        }
+    }
+    fn name(&self) -> String {
+        String::from("one-cycle")
+    }
+}
+
+/**Reduces the learning rate on a plateau: multiplies it by factor whenever the loss
+ * has not improved on its best for patience epochs in a row */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plateau {
+    pub patience: usize,
+    pub factor: f64,
+}
+
+impl Schedule for Plateau {
+    fn learn_rate(&self, _: usize, _: usize, base: f64, losses: &[f64]) -> f64 {
+        let (mut best, mut waited, mut rate) = (f64::INFINITY, 0, base);
+        for &loss in losses {
+            if loss < best {
+                best = loss;
+                waited = 0;
+            } else {
+                waited += 1;
+                if waited >= self.patience {
+                    rate *= self.factor;
+                    waited = 0;
+                }
+            }
+        }
+        rate
+    }
+    fn name(&self) -> String {
+        format!("plateau:{}:{}", self.patience, self.factor)
+    }
+}
@@ -1,8 +1,13 @@
 use ndarray::{Array, Array2};
-use ndarray_rand::rand_distr::Uniform;
-use ndarray_rand::RandomExt;
+use ndarray_rand::rand::rngs::StdRng;
+use ndarray_rand::rand::{thread_rng, Rng, SeedableRng};
 
+use crate::dataset::Dataset;
+use crate::networks::activation::{Activation, OutputActivation, Sigmoid};
+use crate::networks::loss::{BinaryCrossEntropy, Loss};
 use crate::networks::neural_network::*;
+use crate::networks::optimizer::{Optimizer, Sgd};
+use crate::normalize::Normalizer;
 
 /**Defining the Neural Network*/
 pub struct SigmoidNetwork {
@@ -16,48 +21,86 @@ pub struct SigmoidNetwork {
     //layers
     hidden_layer: Array2<f64>,
     output_layer: Array2<f64>,
+    //the weighted sums of the hidden layer, before its activation function is applied
+    hidden_sum: Array2<f64>,
+    //the activation function of the hidden layer
+    activation: Box<dyn Activation>,
+    //preprocessing of the inputs
+    normalizer: Option<Normalizer>,
+    //the activation function of the output layer
+    output: OutputActivation,
+    //the loss function minimized by training
+    loss: Box<dyn Loss>,
+    //updates the weights and biases from their gradients
+    optimizer: Box<dyn Optimizer>,
 }
 
 /**Implementing the unique activation functions for this implementation of the network */
 impl SigmoidNetwork {
-    /**Constructor-like function that initializes and returns a network */
+    /**Constructor-like function that initializes and returns a network for MNIST,
+     * with 784 inputs, a hidden layer of 20 and 10 outputs */
     pub fn new() -> Self {
+        Self::with_sizes(784, HIDDEN_SIZE, 10)
+    }
+
+    /**Initializes a network sized to the inputs and classes of a data set */
+    pub fn for_dataset(data: &impl Dataset) -> Self {
+        Self::with_sizes(data.input_dim(), HIDDEN_SIZE, data.num_classes())
+    }
+
+    /**Initializes a network with the given number of inputs, hidden units and outputs */
+    pub fn with_sizes(inputs: usize, hidden: usize, outputs: usize) -> Self {
+        Self::with_rng(inputs, hidden, outputs, &mut thread_rng())
+    }
+
+    /**Initializes a network like with_sizes, whose weights are the same for the same seed */
+    pub fn with_seed(inputs: usize, hidden: usize, outputs: usize, seed: u64) -> Self {
+        Self::with_rng(inputs, hidden, outputs, &mut StdRng::seed_from_u64(seed))
+    }
+
+    /*Initializes a network, drawing its random weights from rng*/
+    fn with_rng(inputs: usize, hidden: usize, outputs: usize, rng: &mut impl Rng) -> Self {
         //initializes each layer and its corresponding weights and biases
         //the hidden and output layers themselves begin empty
+        let activation: Box<dyn Activation> = Box::new(Sigmoid);
+        let output = OutputActivation::Sigmoid;
         Self {
-            //initial values of weights are randomized
+            //initial values of weights are randomized, scaled to suit each layer's activation
             //hidden
             hidden_layer: Array::default((0, 0)),
-            hidden_weights: Array::random((20, 784), Uniform::new(-0.5, 0.5)),
-            hidden_bias: Array::zeros((20, 1)),
+            hidden_sum: Array::default((0, 0)),
+            hidden_weights: activation.initializer().init((hidden, inputs), rng),
+            hidden_bias: Array::zeros((hidden, 1)),
             //output
             output_layer: Array::default((0, 0)),
-            output_weights: Array::random((10, 20), Uniform::new(-0.5, 0.5)),
-            output_bias: Array::zeros((10, 1)),
+            output_weights: output.initializer().init((outputs, hidden), rng),
+            output_bias: Array::zeros((outputs, 1)),
+            //inputs are used as they are until a normalizer is set
+            normalizer: None,
+            activation,
+            output,
+            loss: Box::new(BinaryCrossEntropy),
+            optimizer: Box::new(Sgd),
         }
     }
 }
 
-/**Implementing the neural net trait for the network using sigmoid for the activation function */
-impl NeuralNetwork for SigmoidNetwork {
-    /**Implementing the activation function as the sigmoid function of x*/
-    fn activation(x: Array2<f64>) -> Array2<f64> {
-        //calling e^x on each element in mapv
-        1.0 / (1.0 + (-x).mapv(|v| v.exp()))
-    }
-
-    /**Implementing the derivative of sigmoid */
-    fn activ_deriv(x: &Array2<f64>) -> Array2<f64> {
-        //A simiplified version of the sigmoid derivative.
-        //should only be used on the hidden layer.
-        x * (1.0 - x)
+impl Default for SigmoidNetwork {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
+/**Implementing the neural net trait for the network using sigmoid for the activation function */
+impl NeuralNetwork for SigmoidNetwork {
     /**Setter and getter functions for each array */
     //Getters
     fn get_hid_layer(&self) -> Array2<f64> {
         self.hidden_layer.to_owned()
     }
+    fn get_hid_sum(&self) -> Array2<f64> {
+        self.hidden_sum.to_owned()
+    }
     fn get_hid_weights(&self) -> Array2<f64> {
         self.hidden_weights.to_owned()
     }
@@ -73,10 +116,31 @@ impl NeuralNetwork for SigmoidNetwork {
     fn get_out_bias(&self) -> Array2<f64> {
         self.output_bias.to_owned()
     }
+    fn get_hid_activation(&self) -> &dyn Activation {
+        self.activation.as_ref()
+    }
+    fn get_normalizer(&self) -> Option<&Normalizer> {
+        self.normalizer.as_ref()
+    }
+    fn get_output_activation(&self) -> OutputActivation {
+        self.output
+    }
+    fn get_loss(&self) -> &dyn Loss {
+        self.loss.as_ref()
+    }
+    fn get_optimizer(&self) -> &dyn Optimizer {
+        self.optimizer.as_ref()
+    }
+    fn get_optimizer_mut(&mut self) -> &mut dyn Optimizer {
+        self.optimizer.as_mut()
+    }
     //Setters
     fn set_hid_layer(&mut self, x: Array2<f64>) {
         self.hidden_layer = x;
     }
+    fn set_hid_sum(&mut self, x: Array2<f64>) {
+        self.hidden_sum = x;
+    }
     fn set_hid_weights(&mut self, x: Array2<f64>) {
         self.hidden_weights = x;
     }
@@ -92,4 +156,19 @@ impl NeuralNetwork for SigmoidNetwork {
     fn set_out_bias(&mut self, x: Array2<f64>) {
         self.output_bias = x;
     }
+    fn set_normalizer(&mut self, x: Option<Normalizer>) {
+        self.normalizer = x;
+    }
+    fn set_hid_activation(&mut self, x: Box<dyn Activation>) {
+        self.activation = x;
+    }
+    fn set_output_activation(&mut self, x: OutputActivation) {
+        self.output = x;
+    }
+    fn set_loss(&mut self, x: Box<dyn Loss>) {
+        self.loss = x;
+    }
+    fn set_optimizer(&mut self, x: Box<dyn Optimizer>) {
+        self.optimizer = x;
+    }
 }
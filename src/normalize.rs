@@ -0,0 +1,135 @@
+use ndarray::{Array1, Array2};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::dataset::Dataset;
+use crate::read_data::DataError;
+
+/**The ways a Normalizer can rescale the inputs */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalizeMode {
+    /**Subtracts the mean of every input value and divides by their standard deviation */
+    Global,
+    /**Standardizes each pixel by its own mean and standard deviation */
+    PerPixel,
+    /**Rescales each pixel so its smallest value becomes 0 and its largest 1 */
+    MinMax,
+}
+
+impl NormalizeMode {
+    /**Looks up a mode by the name used on the command line (eg. per-pixel) */
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "global" => Some(Self::Global),
+            "per-pixel" => Some(Self::PerPixel),
+            "min-max" => Some(Self::MinMax),
+            _ => None,
+        }
+    }
+}
+
+/**Preprocessing fit on the training data, computing (x - offset) / scale for each input value.
+ * The same statistics must be used for training, testing and inference, so a network keeps
+ * the Normalizer it was trained with and applies it to every input itself. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Normalizer {
+    offset: Array1<f64>,
+    scale: Array1<f64>,
+}
+
+impl Normalizer {
+    /**Computes the statistics of every input in a data set, which must not be empty */
+    pub fn fit(data: &impl Dataset, mode: NormalizeMode) -> Result<Self, DataError> {
+        if data.is_empty() {
+            return Err(DataError::NoSamples {
+                what: String::from("fit the normalizer on"),
+            });
+        }
+        let dim = data.input_dim();
+        let n = data.len() as f64;
+        let (mut sum, mut sum_sq) = (Array1::<f64>::zeros(dim), Array1::<f64>::zeros(dim));
+        let (mut min, mut max) = (
+            Array1::from_elem(dim, f64::INFINITY),
+            Array1::from_elem(dim, f64::NEG_INFINITY),
+        );
+        //a single pass over the samples, converting one at a time
+        for i in 0..data.len() {
+            let (x, _) = data.get(i);
+            sum += &x;
+            sum_sq += &(&x * &x);
+            min.zip_mut_with(&x, |m, &v| *m = m.min(v));
+            max.zip_mut_with(&x, |m, &v| *m = m.max(v));
+        }
+        let (offset, spread) = match mode {
+            NormalizeMode::Global => {
+                let count = n * dim as f64;
+                let mean = sum.sum() / count;
+                let var = sum_sq.sum() / count - mean * mean;
+                (
+                    Array1::from_elem(dim, mean),
+                    Array1::from_elem(dim, var.max(0.0).sqrt()),
+                )
+            }
+            NormalizeMode::PerPixel => {
+                let mean = &sum / n;
+                let var = &sum_sq / n - &mean * &mean;
+                (mean, var.mapv(|v| v.max(0.0).sqrt()))
+            }
+            NormalizeMode::MinMax => {
+                let range = &max - &min;
+                (min, range)
+            }
+        };
+        //inputs that never change (eg. the blank border pixels) are only shifted
+        let scale = spread.mapv(|s| if s > 1e-12 { s } else { 1.0 });
+        Ok(Self { offset, scale })
+    }
+
+    /**Normalizes a single input */
+    pub fn apply(&self, x: &Array1<f64>) -> Array1<f64> {
+        (x - &self.offset) / &self.scale
+    }
+
+    /**Normalizes every row of a matrix of inputs */
+    pub fn apply_rows(&self, x: &Array2<f64>) -> Array2<f64> {
+        //the statistics are broadcast across the rows
+        (x - &self.offset) / &self.scale
+    }
+
+    /**Writes the statistics to a text file, one "offset scale" pair per line */
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let lines: Vec<String> = self
+            .offset
+            .iter()
+            .zip(self.scale.iter())
+            .map(|(o, s)| format!("{} {}", o, s))
+            .collect();
+        fs::write(path, lines.join("\n") + "\n")
+    }
+
+    /**Reads statistics written by save, which must be finite with a scale above 0 */
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let invalid = |line: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid normalizer line: {}", line),
+            )
+        };
+        let (mut offset, mut scale) = (Vec::new(), Vec::new());
+        for line in fs::read_to_string(path)?.lines() {
+            let mut values = line.split_whitespace().map(str::parse::<f64>);
+            match (values.next(), values.next(), values.next()) {
+                (Some(Ok(o)), Some(Ok(s)), None) if o.is_finite() && s.is_finite() && s > 0.0 => {
+                    offset.push(o);
+                    scale.push(s);
+                }
+                _ => return Err(invalid(line)),
+            }
+        }
+        Ok(Self {
+            offset: Array1::from(offset),
+            scale: Array1::from(scale),
+        })
+    }
+}
@@ -1,86 +1,791 @@
 use csv::ReaderBuilder;
+use flate2::read::MultiGzDecoder;
 use ndarray::{s, Array, Array1, Array2};
 use ndarray_csv::Array2Reader;
-use raster::{self, Color};
+use raster::{self, error::RasterError, Color};
 use std::error::Error;
+use std::fmt;
 use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use crate::cache::read_cached;
+
+//magic numbers of the IDX files used by the MNIST data set (unsigned bytes, 1 or 3 dimensions)
+const IDX_LABEL_MAGIC: u32 = 0x0000_0801;
+const IDX_IMAGE_MAGIC: u32 = 0x0000_0803;
+//the first two bytes of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+//where MNIST is looked for when no other location is given
+pub const DEFAULT_DATA_DIR: &str = "mnist_data";
+//environment variable that overrides the default MNIST directory
+pub const DATA_DIR_VAR: &str = "MNIST_DATA_DIR";
+//where show_image writes the selected digit
+const SHOW_IMAGE_PATH: &str = "selected_img.png";
+
+/**The errors that can occur while loading data or writing images,
+ * each naming the file involved where there is one */
+#[derive(Debug)]
+pub enum DataError {
+    /**A file could not be opened, read or written */
+    Io { path: PathBuf, source: io::Error },
+    /**A file's contents are not in the expected format */
+    Parse { path: PathBuf, message: String },
+    /**The dimensions or number of items do not match what was expected */
+    ShapeMismatch {
+        what: String,
+        expected: usize,
+        found: usize,
+    },
+    /**A label is not a whole number within the data set's classes,
+     * naming the file it was read from when there is one */
+    LabelOutOfRange {
+        path: Option<PathBuf>,
+        index: usize,
+        label: f64,
+        classes: usize,
+    },
+    /**The label smoothing is not a fraction between 0 and 1 */
+    InvalidSmoothing { smoothing: f64 },
+    /**There are no samples to compute something from, eg. the statistics of a normalizer */
+    NoSamples { what: String },
+    /**A file, or the requested part of it, holds no images */
+    EmptyDataset { path: PathBuf },
+    /**An image could not be encoded */
+    Image { path: PathBuf, message: String },
+}
+
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => {
+                write!(f, "could not access {}: {}", path.display(), source)
+            }
+            Self::Parse { path, message } => {
+                write!(f, "could not parse {}: {}", path.display(), message)
+            }
+            Self::ShapeMismatch {
+                what,
+                expected,
+                found,
+            } => write!(f, "{}: expected {}, found {}", what, expected, found),
+            Self::LabelOutOfRange {
+                path,
+                index,
+                label,
+                classes,
+            } => {
+                if let Some(path) = path {
+                    write!(f, "{}: ", path.display())?;
+                }
+                write!(
+                    f,
+                    "label {} of item {} is not a whole number between 0 and {}",
+                    label,
+                    index,
+                    classes.saturating_sub(1)
+                )
+            }
+            Self::InvalidSmoothing { smoothing } => write!(
+                f,
+                "label smoothing must be between 0 and 1, got {}",
+                smoothing
+            ),
+            Self::NoSamples { what } => write!(f, "there are no samples to {}", what),
+            Self::EmptyDataset { path } => write!(f, "{} holds no images", path.display()),
+            Self::Image { path, message } => {
+                write!(f, "could not write image {}: {}", path.display(), message)
+            }
+        }
+    }
+}
+
+impl Error for DataError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl DataError {
+    /*Attaches the path of the file being read to an I/O error*/
+    fn io(path: &Path) -> impl FnOnce(io::Error) -> Self + '_ {
+        move |source| Self::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+}
+
+//class names of each data set, indexed by label
+const DIGIT_NAMES: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+const FASHION_NAMES: [&str; 10] = [
+    "T-shirt/top",
+    "Trouser",
+    "Pullover",
+    "Dress",
+    "Coat",
+    "Sandal",
+    "Shirt",
+    "Sneaker",
+    "Bag",
+    "Ankle boot",
+];
+const KMNIST_NAMES: [&str; 10] = ["o", "ki", "su", "tsu", "na", "ha", "ma", "ya", "re", "wo"];
+const LETTER_NAMES: [&str; 26] = [
+    "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S",
+    "T", "U", "V", "W", "X", "Y", "Z",
+];
+//the balanced split merges the lowercase letters that look like their uppercase versions
+const BALANCED_NAMES: [&str; 47] = [
+    "0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "A", "B", "C", "D", "E", "F", "G", "H", "I",
+    "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S", "T", "U", "V", "W", "X", "Y", "Z", "a", "b",
+    "d", "e", "f", "g", "h", "n", "q", "r", "t",
+];
+
+/**The data sets that share MNIST's layout of 28x28 grayscale images in IDX files,
+ * each defining its own classes */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatasetKind {
+    Mnist,
+    FashionMnist,
+    Kmnist,
+    EmnistBalanced,
+    EmnistDigits,
+    EmnistLetters,
+}
+
+impl DatasetKind {
+    /**Looks up a data set by the name used on the command line (eg. fashion-mnist) */
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "mnist" => Some(Self::Mnist),
+            "fashion-mnist" | "fashion" => Some(Self::FashionMnist),
+            "kmnist" => Some(Self::Kmnist),
+            "emnist-balanced" => Some(Self::EmnistBalanced),
+            "emnist-digits" => Some(Self::EmnistDigits),
+            "emnist-letters" => Some(Self::EmnistLetters),
+            _ => None,
+        }
+    }
+
+    /**The names of the classes, indexed by class */
+    pub fn class_names(self) -> &'static [&'static str] {
+        match self {
+            Self::Mnist | Self::EmnistDigits => &DIGIT_NAMES,
+            Self::FashionMnist => &FASHION_NAMES,
+            Self::Kmnist => &KMNIST_NAMES,
+            Self::EmnistBalanced => &BALANCED_NAMES,
+            Self::EmnistLetters => &LETTER_NAMES,
+        }
+    }
+
+    /**The directory the data set is read from when no other is given. Fashion-MNIST and
+     * KMNIST use the same file names as MNIST, so each needs a directory of its own */
+    pub fn default_dir(self) -> &'static str {
+        match self {
+            Self::Mnist => DEFAULT_DATA_DIR,
+            Self::FashionMnist => "fashion_mnist_data",
+            Self::Kmnist => "kmnist_data",
+            Self::EmnistBalanced | Self::EmnistDigits | Self::EmnistLetters => "emnist_data",
+        }
+    }
+
+    /**The number of classes the images are labelled with */
+    pub fn num_classes(self) -> usize {
+        self.class_names().len()
+    }
+
+    /*The value of the first class's label in the files,
+     * the EMNIST letters are labelled from 1 to 26*/
+    fn first_label(self) -> f64 {
+        match self {
+            Self::EmnistLetters => 1.0,
+            _ => 0.0,
+        }
+    }
+
+    /*EMNIST stores its images transposed, with the columns of each image written first*/
+    fn is_transposed(self) -> bool {
+        matches!(
+            self,
+            Self::EmnistBalanced | Self::EmnistDigits | Self::EmnistLetters
+        )
+    }
+
+    /*The names of a split's image, label and csv files*/
+    fn file_names(self, training: bool) -> (String, String, Option<String>) {
+        let split = if training { "train" } else { "test" };
+        let prefix = match self {
+            Self::EmnistBalanced => format!("emnist-balanced-{}", split),
+            Self::EmnistDigits => format!("emnist-digits-{}", split),
+            Self::EmnistLetters => format!("emnist-letters-{}", split),
+            //MNIST and its drop-in replacements name their test split t10k
+            _ if training => String::from("train"),
+            _ => String::from("t10k"),
+        };
+        let csv = match self {
+            Self::Mnist => Some(format!("mnist_{}.csv", split)),
+            Self::FashionMnist => Some(format!("fashion-mnist_{}.csv", split)),
+            _ => None,
+        };
+        (
+            format!("{}-images-idx3-ubyte", prefix),
+            format!("{}-labels-idx1-ubyte", prefix),
+            csv,
+        )
+    }
+}
+
+/**Where one split of the data set is read from, either a csv export
+ * (label in the first column) or a pair of IDX image and label files */
+#[derive(Debug, Clone)]
+pub enum DataSource {
+    Csv(PathBuf),
+    Idx { images: PathBuf, labels: PathBuf },
+}
+
+impl DataSource {
+    /**Finds a data set's training split inside a data directory */
+    pub fn training(dir: &Path, kind: DatasetKind) -> Self {
+        Self::in_dir(dir, kind.file_names(true))
+    }
+
+    /**Finds a data set's testing split inside a data directory */
+    pub fn testing(dir: &Path, kind: DatasetKind) -> Self {
+        Self::in_dir(dir, kind.file_names(false))
+    }
+
+    /**Uses a single file given by the user. IDX image files are paired with the
+     * label file next to them (eg. train-images-idx3-ubyte with train-labels-idx1-ubyte) */
+    pub fn from_file(path: &Path) -> Self {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if name.contains("-images-idx3-ubyte") {
+            let labels =
+                path.with_file_name(name.replace("-images-idx3-ubyte", "-labels-idx1-ubyte"));
+            Self::Idx {
+                images: path.to_path_buf(),
+                labels,
+            }
+        } else {
+            Self::Csv(path.to_path_buf())
+        }
+    }
+
+    /*Prefers the IDX files when they are present, otherwise uses the csv export if there is one*/
+    fn in_dir(dir: &Path, (images, labels, csv): (String, String, Option<String>)) -> Self {
+        //any of the files may also be stored gzipped
+        match (
+            find_data_file(&dir.join(&images)),
+            find_data_file(&dir.join(&labels)),
+            csv,
+        ) {
+            (Some(images), Some(labels), _) => Self::Idx { images, labels },
+            //falling back to the plain file names so that errors point at them
+            (_, _, Some(csv)) => {
+                Self::Csv(find_data_file(&dir.join(&csv)).unwrap_or_else(|| dir.join(csv)))
+            }
+            (_, _, None) => Self::Idx {
+                images: dir.join(images),
+                labels: dir.join(labels),
+            },
+        }
+    }
+
+    /**Returns the file the split's labels are read from */
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Csv(path) => path,
+            Self::Idx { labels, .. } => labels,
+        }
+    }
+
+    /**Reads the split into a 2-D Array of bytes with the label in the first column */
+    pub fn read(&self) -> Result<Array2<u8>, DataError> {
+        match self {
+            Self::Csv(path) => to_bytes(read_csv(path)?, path),
+            Self::Idx { images, labels } => read_idx(images, labels),
+        }
+    }
+}
+
+/**Returns the data directory of a data set: the one named by the MNIST_DATA_DIR environment
+ * variable for MNIST when it is set, otherwise the data set's default directory */
+pub fn data_dir_from_env(kind: DatasetKind) -> PathBuf {
+    std::env::var_os(DATA_DIR_VAR)
+        .filter(|_| kind == DatasetKind::Mnist)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(kind.default_dir()))
+}
+
+/*Opens a data file for reading, decompressing it on the fly if it is gzipped*/
+pub fn open_data(path: &Path) -> Result<Box<dyn Read>, DataError> {
+    let file = File::open(path).map_err(DataError::io(path))?;
+    let mut reader = BufReader::new(file);
+    //peeking at the start of the file without consuming it
+    let is_gzip = reader
+        .fill_buf()
+        .map_err(DataError::io(path))?
+        .starts_with(&GZIP_MAGIC);
+    if is_gzip {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(reader))))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/*Returns the path of a data file if it exists, either as is or with a .gz extension*/
+fn find_data_file(path: &Path) -> Option<PathBuf> {
+    let mut gz = path.as_os_str().to_owned();
+    gz.push(".gz");
+    [path.to_path_buf(), PathBuf::from(gz)]
+        .into_iter()
+        .find(|p| p.exists())
+}
 
 /*Reads the data from a csv file into a 2-D Array, may return error*/
-pub fn read_csv(path: &str) -> Result<Array2<f64>, Box<dyn Error>> {
-    let file_path = File::open(path)?;
-    let mut reader = ReaderBuilder::new().from_reader(file_path);
+pub fn read_csv(path: &Path) -> Result<Array2<f64>, DataError> {
+    let file = open_data(path)?;
+    let mut reader = ReaderBuilder::new().from_reader(file);
     //reading file into 2-D array
-    let data: Array2<f64> = reader.deserialize_array2_dynamic()?;
+    let data: Array2<f64> = reader
+        .deserialize_array2_dynamic()
+        .map_err(|e| DataError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
     //return data if there is no error
     Ok(data)
 }
 
+/*Reads the 4 byte big-endian integers that make up an IDX header*/
+fn read_u32(reader: &mut impl Read, path: &Path) -> Result<u32, DataError> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf).map_err(DataError::io(path))?;
+    Ok(u32::from_be_bytes(buf))
+}
+
+/*Reads the header of an IDX file, checking its magic number and returning its dimensions*/
+fn read_idx_header(
+    reader: &mut impl Read,
+    magic: u32,
+    path: &Path,
+) -> Result<Vec<usize>, DataError> {
+    let found = read_u32(reader, path)?;
+    if found != magic {
+        return Err(DataError::Parse {
+            path: path.to_path_buf(),
+            message: format!(
+                "not a valid IDX file (magic number {:#010x}, expected {:#010x})",
+                found, magic
+            ),
+        });
+    }
+    //the lowest byte of the magic number holds the number of dimensions
+    let mut dims = Vec::new();
+    for _ in 0..(magic & 0xff) {
+        dims.push(read_u32(reader, path)? as usize);
+    }
+    Ok(dims)
+}
+
+/*Reads the body of an IDX file, checking that it holds exactly the expected number of bytes.
+ * The header is not trusted to allocate for, at most one byte more than expected is read*/
+fn read_idx_body(
+    reader: &mut impl Read,
+    expected: usize,
+    path: &Path,
+) -> Result<Vec<u8>, DataError> {
+    let mut data = Vec::new();
+    reader
+        .take(expected as u64 + 1)
+        .read_to_end(&mut data)
+        .map_err(DataError::io(path))?;
+    if data.len() != expected {
+        return Err(DataError::ShapeMismatch {
+            what: format!("bytes of data in {}", path.display()),
+            expected,
+            found: data.len(),
+        });
+    }
+    Ok(data)
+}
+
+/*Converts the values of a csv file to bytes, which every label and pixel value must fit in*/
+fn to_bytes(data: Array2<f64>, path: &Path) -> Result<Array2<u8>, DataError> {
+    match data
+        .iter()
+        .find(|&&v| !((0.0..=255.0).contains(&v) && v.fract() == 0.0))
+    {
+        Some(v) => Err(DataError::Parse {
+            path: path.to_path_buf(),
+            message: format!("{} is not a value between 0 and 255", v),
+        }),
+        None => Ok(data.mapv(|v| v as u8)),
+    }
+}
+
+/*Reads an IDX image file (eg. train-images-idx3-ubyte) into a 2-D Array with one image per row*/
+pub fn read_idx_images(path: &Path) -> Result<Array2<u8>, DataError> {
+    let mut reader = open_data(path)?;
+    let dims = read_idx_header(&mut reader, IDX_IMAGE_MAGIC, path)?;
+    //dimensions are the number of images, then the rows and columns of each image
+    let (count, rows, cols) = (dims[0], dims[1], dims[2]);
+    if rows == 0 || cols == 0 {
+        return Err(DataError::Parse {
+            path: path.to_path_buf(),
+            message: format!("invalid image dimensions {}x{}", rows, cols),
+        });
+    }
+    let expected = count
+        .checked_mul(rows)
+        .and_then(|n| n.checked_mul(cols))
+        .ok_or_else(|| DataError::Parse {
+            path: path.to_path_buf(),
+            message: format!("{} images of {}x{} pixels are too many", count, rows, cols),
+        })?;
+    let data = read_idx_body(&mut reader, expected, path)?;
+    //each image is flattened into a row, the same as in the csv files
+    Ok(Array::from_shape_vec((count, rows * cols), data)
+        .expect("body length was checked against the header"))
+}
+
+/*Reads an IDX label file (eg. train-labels-idx1-ubyte) into a 1-D Array*/
+pub fn read_idx_labels(path: &Path) -> Result<Array1<u8>, DataError> {
+    let mut reader = open_data(path)?;
+    let dims = read_idx_header(&mut reader, IDX_LABEL_MAGIC, path)?;
+    let data = read_idx_body(&mut reader, dims[0], path)?;
+    Ok(Array1::from(data))
+}
+
+/*Reads a pair of IDX files into a 2-D Array laid out like the csv files,
+ * with the label in the first column followed by the pixel values*/
+pub fn read_idx(images_path: &Path, labels_path: &Path) -> Result<Array2<u8>, DataError> {
+    let x = read_idx_images(images_path)?;
+    let y = read_idx_labels(labels_path)?;
+    //both files must describe the same number of items
+    if x.dim().0 != y.len() {
+        return Err(DataError::ShapeMismatch {
+            what: format!(
+                "images in {} to match the labels in {}",
+                images_path.display(),
+                labels_path.display()
+            ),
+            expected: y.len(),
+            found: x.dim().0,
+        });
+    }
+    let mut data = Array::zeros((y.len(), x.dim().1 + 1));
+    data.column_mut(0).assign(&y);
+    data.slice_mut(s![.., 1..]).assign(&x);
+    Ok(data)
+}
+
+/**Checks that every label is a whole number naming one of the classes,
+ * rejecting negative, fractional and out of range labels */
+pub fn check_labels(y: &Array1<f64>, classes: usize, path: Option<&Path>) -> Result<(), DataError> {
+    match y
+        .iter()
+        .position(|&v| !(v >= 0.0 && v.fract() == 0.0 && v < classes as f64))
+    {
+        Some(index) => Err(DataError::LabelOutOfRange {
+            path: path.map(Path::to_path_buf),
+            index,
+            label: y[index],
+            classes,
+        }),
+        None => Ok(()),
+    }
+}
+
+/*Reads a split of a data set, checking that it holds at least size images (all of them if None)
+ * and that their labels name the data set's classes. Returns the raw pixel values of those images,
+ * upright, and their classes counted from 0.*/
+pub(crate) fn read_split(
+    source: &DataSource,
+    kind: DatasetKind,
+    size: Option<usize>,
+) -> Result<(Array2<u8>, Array1<usize>), DataError> {
+    let data = read_cached(source)?;
+    let total = data.dim().0;
+    let size = size.unwrap_or(total);
+    if total == 0 || size == 0 {
+        return Err(DataError::EmptyDataset {
+            path: source.path().to_path_buf(),
+        });
+    }
+    if size > total {
+        return Err(DataError::ShapeMismatch {
+            what: format!("images available in {}", source.path().display()),
+            expected: size,
+            found: total,
+        });
+    }
+    //the label followed by 28x28 pixels
+    if data.dim().1 != 785 {
+        return Err(DataError::ShapeMismatch {
+            what: format!("columns in {}", source.path().display()),
+            expected: 785,
+            found: data.dim().1,
+        });
+    }
+    let y: Array1<f64> = data.slice(s![..size, 0]).mapv(|v| v as f64) - kind.first_label();
+    check_labels(&y, kind.num_classes(), Some(source.path()))?;
+    let mut x: Array2<u8> = data.slice(s![..size, 1..]).to_owned();
+    if kind.is_transposed() {
+        for mut row in x.rows_mut() {
+            let upright = row.to_owned().into_shape((28, 28)).unwrap().reversed_axes();
+            row.assign(&Array::from_iter(upright.iter().copied()));
+        }
+    }
+    Ok((x, y.mapv(|v| v as usize)))
+}
+
+/**Converts raw pixel values to floats between 0 and 1, dividing by the max pixel value */
+pub fn normalize_pixels(x: &Array2<u8>) -> Array2<f64> {
+    x.mapv(|v| v as f64 / 255.0)
+}
+
 /**One hot encoding involves taking an array with categorical data, and
- * encoding that categorical portion into 1's and 0's */
-pub fn one_hot_encode(y: &Array1<f64>) -> Array2<f64> {
-    //the maximum value in this case will be the number of categories
-    let max = y.fold(f64::MIN, |a, &b| a.max(b)) as usize;
-    let mut one_hot_y = Array::zeros((y.len(), max + 1));
-    //starting with 0's, place 1's in corresponding locations
+ * encoding that categorical portion into 1's and 0's.
+ * classes - The number of categories, which comes from the data set rather than the labels,
+ * as a subset may not contain the highest class */
+pub fn one_hot_encode(y: &Array1<f64>, classes: usize) -> Result<Array2<f64>, DataError> {
+    one_hot_encode_smoothed(y, classes, 0.0)
+}
+
+/**One hot encoding with label smoothing, which moves a fraction of each label's certainty
+ * evenly onto every class, so the network is not pushed towards overconfident outputs.
+ * eg. with 10 classes and a smoothing of 0.1, labels are 0.91 for the class and 0.01 otherwise
+ * smoothing - The fraction to spread out, between 0 (plain one hot encoding) and 1
+ */
+pub fn one_hot_encode_smoothed(
+    y: &Array1<f64>,
+    classes: usize,
+    smoothing: f64,
+) -> Result<Array2<f64>, DataError> {
+    //NaN is not contained in any range either
+    if !(0.0..=1.0).contains(&smoothing) {
+        return Err(DataError::InvalidSmoothing { smoothing });
+    }
+    check_labels(y, classes, None)?;
+    //starting with the smoothed share of every class, then adding the rest to each label's class
+    let mut one_hot_y = Array::from_elem((y.len(), classes), smoothing / classes as f64);
     for i in 0..y.len() {
         let y_val = y[i] as usize;
-        one_hot_y[[i, y_val]] = 1.0;
+        one_hot_y[[i, y_val]] += 1.0 - smoothing;
     }
-    one_hot_y
+    Ok(one_hot_y)
 }
 
 /**Returns the training data in two arrays, x containing the pixel values, and y the label
+ * source - Where to read the training split from
+ * kind - Which data set the split belongs to
  * size - How many images to retrieve from the data set
 */
-pub fn get_training_data(size: usize) -> (Array2<f64>, Array2<f64>) {
+pub fn get_training_data(
+    source: &DataSource,
+    kind: DatasetKind,
+    size: usize,
+) -> Result<(Array2<f64>, Array2<f64>), DataError> {
     //acquiring train data
-    let train_file = r".\mnist_data\mnist_train.csv";
-    let train_data = read_csv(train_file)
-        .expect("Could not read csv.")
-        .t()
-        .to_owned();
-    let y: Array1<f64> = train_data.slice(s![0, ..size]).to_owned();
+    let (x, y) = read_split(source, kind, Some(size))?;
     //encoding training data by labels (eg. 0-9 for each digit)
-    let o_h_y = one_hot_encode(&y);
-    let mut x: Array2<f64> = train_data
-        .slice(s![1..train_data.dim().0, ..size])
-        .t()
-        .to_owned();
-    //dividing by max pixel value to convert values to be between 0 and 1
-    x /= 255.0;
-    (x, o_h_y)
-}
-
-/**Returns testing data in two arrays, x containing the pixel values, and y the label */
-pub fn get_testing_data() -> (Array2<f64>, Array1<f64>) {
+    let o_h_y = one_hot_encode(&y.mapv(|v| v as f64), kind.num_classes())?;
+    //converting values to be between 0 and 1
+    Ok((normalize_pixels(&x), o_h_y))
+}
+
+/**Returns testing data in two arrays, x containing the pixel values, and y the label
+ * source - Where to read the testing split from
+ * kind - Which data set the split belongs to
+*/
+pub fn get_testing_data(
+    source: &DataSource,
+    kind: DatasetKind,
+) -> Result<(Array2<f64>, Array1<f64>), DataError> {
     //acquiring test data
-    let test_file = r".\mnist_data\mnist_test.csv";
-    let test_data = read_csv(test_file)
-        .expect("Could not read csv.")
-        .t()
-        .to_owned();
-    //seperating data into x and y axes
-    let y: Array1<f64> = test_data.slice(s![0, ..]).to_owned();
-    let mut x: Array2<f64> = test_data.slice(s![1..test_data.dim().0, ..]).t().to_owned();
+    let (x, y) = read_split(source, kind, None)?;
     //converting to 0 to 1 interval
-    x /= 255.0;
-    (x, y)
+    Ok((normalize_pixels(&x), y.mapv(|v| v as f64)))
 }
 
 /**Generates an image of the selected digit using the pixel data from its array */
-pub fn show_image(img: &Array1<f64>) {
-    //starting with a blank image, all images in the MNIST set are 28x28.
+pub fn show_image(img: &Array1<f64>) -> Result<(), DataError> {
+    //all images in the MNIST set are 28x28.
+    if img.len() != 784 {
+        return Err(DataError::ShapeMismatch {
+            what: String::from("pixels in the image"),
+            expected: 784,
+            found: img.len(),
+        });
+    }
+    //starting with a blank image
     let mut result = raster::Image::blank(28, 28);
     let mut p = 0;
     for i in 0..result.height {
         for j in 0..result.width {
             //retrieving the original rgb value by multiplying by 255.
-            let rgb_val = (img.get(p).unwrap() * 255.0) as u8;
+            let rgb_val = (img[p] * 255.0) as u8;
             result
                 .set_pixel(j, i, Color::rgba(rgb_val, rgb_val, rgb_val, 255))
-                .unwrap();
+                .expect("pixel lies within the image");
             p += 1;
         }
     }
-    raster::save(&result, "selected_img.png").unwrap();
+    raster::save(&result, SHOW_IMAGE_PATH).map_err(|e| raster_error(Path::new(SHOW_IMAGE_PATH), e))
+}
+
+/*Converts an error from raster, keeping io errors apart from image format errors*/
+pub(crate) fn raster_error(path: &Path, e: RasterError) -> DataError {
+    match e {
+        RasterError::Io(source) => DataError::Io {
+            path: path.to_path_buf(),
+            source,
+        },
+        other => DataError::Image {
+            path: path.to_path_buf(),
+            message: format!("{:?}", other),
+        },
+    }
+}
+
+/**Reads a PNG or JPEG image of a handwritten character and prepares it the way the MNIST
+ * images were: converted to grayscale with light strokes on a dark background, cropped to
+ * the character, scaled so its longer side is 20 pixels, and placed in a 28x28 image with
+ * its center of mass in the middle. The result can be given to make_guess like a test image.
+ * Dark strokes on a light background are inverted, which is detected from the image's border.
+ * path - The image file, any size, whose type is chosen by its extension
+*/
+pub fn load_image(path: &Path) -> Result<Array1<f64>, DataError> {
+    let image = raster::open(&path.to_string_lossy()).map_err(|e| raster_error(path, e))?;
+    let (width, height) = (image.width as usize, image.height as usize);
+    //grayscale pngs are left with fewer than 4 bytes per pixel by raster
+    let channels = match width * height {
+        0 => 0,
+        pixels => image.bytes.len() / pixels,
+    };
+    if !(1..=4).contains(&channels) || image.bytes.len() != width * height * channels {
+        return Err(DataError::Image {
+            path: path.to_path_buf(),
+            message: String::from("unsupported pixel format"),
+        });
+    }
+    //brightness between 0 and 1, with transparent parts shown over white
+    let gray = Array2::from_shape_fn((height, width), |(row, col)| {
+        let px = &image.bytes[(row * width + col) * channels..][..channels];
+        let (value, alpha) = match channels {
+            1 => (px[0] as f64, 255.0),
+            2 => (px[0] as f64, px[1] as f64),
+            _ => (
+                0.299 * px[0] as f64 + 0.587 * px[1] as f64 + 0.114 * px[2] as f64,
+                if channels == 4 { px[3] as f64 } else { 255.0 },
+            ),
+        };
+        (value * alpha / 255.0 + (255.0 - alpha)) / 255.0
+    });
+    let ink = to_ink(gray).ok_or_else(|| DataError::Image {
+        path: path.to_path_buf(),
+        message: String::from("no character found in the image"),
+    })?;
+    Ok(Array1::from_iter(center_in_field(&fit_to_box(&ink))))
+}
+
+//size of the box the character is scaled into, and of the image it is centered in
+const CHAR_BOX: usize = 20;
+const FIELD: usize = 28;
+//strokes fainter than this (after stretching to 0 to 1) are not part of the character
+const INK_THRESHOLD: f64 = 0.2;
+
+/*Turns brightness into stroke strength: the background, taken from the border, becomes 0
+ * and the strongest stroke 1. Returns the image cropped to the character, or None if blank*/
+fn to_ink(gray: Array2<f64>) -> Option<Array2<f64>> {
+    let (height, width) = gray.dim();
+    let border: Vec<f64> = gray
+        .indexed_iter()
+        .filter(|((r, c), _)| *r == 0 || *c == 0 || *r == height - 1 || *c == width - 1)
+        .map(|(_, &v)| v)
+        .collect();
+    let background = border.iter().sum::<f64>() / border.len().max(1) as f64;
+    //MNIST strokes are light, so dark writing on a light background is inverted
+    let ink = if background > 0.5 {
+        gray.mapv(|v| 1.0 - v)
+    } else {
+        gray
+    };
+    let background = background.min(1.0 - background);
+    let peak = ink.fold(0.0_f64, |m, &v| m.max(v));
+    if peak - background < 1e-3 {
+        return None;
+    }
+    let ink = ink.mapv(|v| ((v - background) / (peak - background)).clamp(0.0, 1.0));
+    //bounding box of the character
+    let (mut top, mut bottom, mut left, mut right) = (height, 0, width, 0);
+    for ((r, c), &v) in ink.indexed_iter() {
+        if v > INK_THRESHOLD {
+            top = top.min(r);
+            bottom = bottom.max(r);
+            left = left.min(c);
+            right = right.max(c);
+        }
+    }
+    if top > bottom {
+        return None;
+    }
+    Some(ink.slice(s![top..=bottom, left..=right]).to_owned())
+}
+
+/*Scales a character so its longer side is CHAR_BOX pixels, keeping its proportions.
+ * Each new pixel is the average of the area of the old image it covers*/
+fn fit_to_box(img: &Array2<f64>) -> Array2<f64> {
+    let (height, width) = img.dim();
+    let ratio = CHAR_BOX as f64 / height.max(width) as f64;
+    let new_h = ((height as f64 * ratio).round() as usize).clamp(1, CHAR_BOX);
+    let new_w = ((width as f64 * ratio).round() as usize).clamp(1, CHAR_BOX);
+    let (step_y, step_x) = (height as f64 / new_h as f64, width as f64 / new_w as f64);
+    //how much of the old pixels from start to end (as fractions) a new pixel covers
+    let coverage = |start: f64, end: f64, len: usize| -> Vec<(usize, f64)> {
+        (start.floor() as usize..(end.ceil() as usize).min(len))
+            .map(|i| (i, end.min(i as f64 + 1.0) - start.max(i as f64)))
+            .filter(|&(_, w)| w > 0.0)
+            .collect()
+    };
+    Array2::from_shape_fn((new_h, new_w), |(r, c)| {
+        let rows = coverage(r as f64 * step_y, (r + 1) as f64 * step_y, height);
+        let cols = coverage(c as f64 * step_x, (c + 1) as f64 * step_x, width);
+        let mut sum = 0.0;
+        for &(i, wy) in &rows {
+            for &(j, wx) in &cols {
+                sum += img[[i, j]] * wy * wx;
+            }
+        }
+        sum / (step_y * step_x)
+    })
+}
+
+/*Places a character in a FIELD x FIELD image so that its center of mass is in the middle*/
+fn center_in_field(img: &Array2<f64>) -> Array2<f64> {
+    let (height, width) = img.dim();
+    let total = img.sum();
+    let (mut mass_y, mut mass_x) = (0.0, 0.0);
+    for ((r, c), &v) in img.indexed_iter() {
+        mass_y += r as f64 * v;
+        mass_x += c as f64 * v;
+    }
+    let middle = (FIELD as f64 - 1.0) / 2.0;
+    //the character is kept whole even if that moves its center of mass off the middle
+    let place = |mass: f64, len: usize| {
+        (middle - mass / total)
+            .round()
+            .clamp(0.0, (FIELD - len) as f64) as usize
+    };
+    let (top, left) = (place(mass_y, height), place(mass_x, width));
+    let mut field = Array2::zeros((FIELD, FIELD));
+    field
+        .slice_mut(s![top..top + height, left..left + width])
+        .assign(img);
+    field
 }
@@ -0,0 +1,136 @@
+use ndarray::{Array1, Array2};
+use ndarray_rand::rand::rngs::StdRng;
+use ndarray_rand::rand::{Rng, SeedableRng};
+use ndarray_rand::rand_distr::{Distribution, Normal};
+
+use crate::dataset::Dataset;
+
+//the images are the size of the MNIST images
+const SIDE: usize = 28;
+const CLASSES: usize = 10;
+//corners of the seven segment box the digits are drawn in, before any variation
+const LEFT: f64 = 9.0;
+const RIGHT: f64 = 19.0;
+const TOP: f64 = 6.0;
+const MIDDLE: f64 = 14.0;
+const BOTTOM: f64 = 22.0;
+//the segments of a seven segment display, from a at the top clockwise to g in the middle
+const SEGMENTS: [((f64, f64), (f64, f64)); 7] = [
+    ((LEFT, TOP), (RIGHT, TOP)),
+    ((RIGHT, TOP), (RIGHT, MIDDLE)),
+    ((RIGHT, MIDDLE), (RIGHT, BOTTOM)),
+    ((LEFT, BOTTOM), (RIGHT, BOTTOM)),
+    ((LEFT, MIDDLE), (LEFT, BOTTOM)),
+    ((LEFT, TOP), (LEFT, MIDDLE)),
+    ((LEFT, MIDDLE), (RIGHT, MIDDLE)),
+];
+//which segments (a to g) are lit for each digit
+const DIGIT_SEGMENTS: [&str; CLASSES] = [
+    "abcdef", "bc", "abdeg", "abcdg", "bcfg", "acdfg", "acdefg", "abc", "abcdefg", "abcdfg",
+];
+
+/**A data set of digit-like 28x28 images, drawn as the strokes of a seven segment display
+ * with a random position, size, slant, stroke width and noise. The same seed always gives
+ * the same images, so the networks can be tested without the MNIST files. */
+pub struct SyntheticDigits {
+    images: Array2<f64>,
+    labels: Array1<usize>,
+}
+
+impl SyntheticDigits {
+    /**Generates the data set, with the digits 0 to 9 taking turns
+     * count - How many images to generate
+     * seed - Seed for the random variations
+     */
+    pub fn generate(count: usize, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let labels = Array1::from_shape_fn(count, |i| i % CLASSES);
+        let mut images = Array2::zeros((count, SIDE * SIDE));
+        for (mut row, &digit) in images.rows_mut().into_iter().zip(&labels) {
+            row.assign(&draw_digit(digit, &mut rng));
+        }
+        Self { images, labels }
+    }
+
+    /**Returns the pixels of every image, one image per row */
+    pub fn images(&self) -> &Array2<f64> {
+        &self.images
+    }
+}
+
+impl Dataset for SyntheticDigits {
+    fn len(&self) -> usize {
+        self.labels.len()
+    }
+    fn input_dim(&self) -> usize {
+        SIDE * SIDE
+    }
+    fn num_classes(&self) -> usize {
+        CLASSES
+    }
+    fn get(&self, i: usize) -> (Array1<f64>, usize) {
+        (self.images.row(i).to_owned(), self.labels[i])
+    }
+    fn class_of(&self, i: usize) -> usize {
+        self.labels[i]
+    }
+}
+
+/**Returns synthetic training data in the same shapes as get_training_data:
+ * x with the pixel values of an image per row, and y with the one hot encoded digits */
+pub fn synthetic_training_data(size: usize, seed: u64) -> (Array2<f64>, Array2<f64>) {
+    SyntheticDigits::generate(size, seed).to_arrays()
+}
+
+/**Returns synthetic testing data in the same shapes as get_testing_data:
+ * x with the pixel values of an image per row, and y with the digits */
+pub fn synthetic_testing_data(size: usize, seed: u64) -> (Array2<f64>, Array1<f64>) {
+    let data = SyntheticDigits::generate(size, seed);
+    let y = data.labels.mapv(|v| v as f64);
+    (data.images, y)
+}
+
+/*Draws one digit with random variations*/
+fn draw_digit(digit: usize, rng: &mut StdRng) -> Array1<f64> {
+    let (dx, dy) = (rng.gen_range(-2.0..=2.0), rng.gen_range(-2.0..=2.0));
+    let scale = rng.gen_range(0.85..=1.15);
+    let slant = rng.gen_range(-0.2..=0.2);
+    let half_width = rng.gen_range(0.3..=0.9);
+    //moving every corner a little, so the strokes are not perfectly straight and even
+    let mut point = |(x, y): (f64, f64)| {
+        let (x, y) = (x + rng.gen_range(-0.7..=0.7), y + rng.gen_range(-0.7..=0.7));
+        let (x, y) = ((x - MIDDLE) * scale, (y - MIDDLE) * scale);
+        (x - slant * y + MIDDLE + dx, y + MIDDLE + dy)
+    };
+    let strokes: Vec<_> = DIGIT_SEGMENTS[digit]
+        .bytes()
+        .map(|segment| {
+            let (start, end) = SEGMENTS[(segment - b'a') as usize];
+            (point(start), point(end))
+        })
+        .collect();
+    let noise = Normal::new(0.0, 0.05).unwrap();
+    Array1::from_shape_fn(SIDE * SIDE, |p| {
+        let (x, y) = ((p % SIDE) as f64, (p / SIDE) as f64);
+        let distance = strokes
+            .iter()
+            .map(|&(start, end)| distance_to_segment((x, y), start, end))
+            .fold(f64::INFINITY, f64::min);
+        //the edges of the strokes fade out over a pixel
+        let ink = (1.0 + half_width - distance).clamp(0.0, 1.0);
+        (ink + noise.sample(rng)).clamp(0.0, 1.0)
+    })
+}
+
+/*The distance from a point to the closest point of a line segment*/
+fn distance_to_segment(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (ab_x, ab_y) = (b.0 - a.0, b.1 - a.1);
+    let length = ab_x * ab_x + ab_y * ab_y;
+    let t = if length > 0.0 {
+        (((p.0 - a.0) * ab_x + (p.1 - a.1) * ab_y) / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (x, y) = (a.0 + t * ab_x - p.0, a.1 + t * ab_y - p.1);
+    (x * x + y * y).sqrt()
+}
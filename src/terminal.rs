@@ -0,0 +1,127 @@
+use ndarray::Array1;
+
+//characters from darkest to brightest, for terminals without unicode
+const ASCII_RAMP: &[char] = &[' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+//unicode shades from darkest to brightest
+const SHADE_RAMP: &[char] = &[' ', '░', '▒', '▓', '█'];
+//pixels at least this bright are drawn as set with Blocks
+const BLOCK_THRESHOLD: f64 = 0.5;
+//ends the colors of a line
+const RESET: &str = "\x1b[0m";
+
+/**How images are drawn in the terminal */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderStyle {
+    /**One line per row of pixels, each pixel drawn as two characters of a brightness ramp */
+    Ascii,
+    /**Two rows of pixels per line using unicode half blocks, each pixel either set or not */
+    Blocks,
+    /**Like Blocks, but with the exact gray of every pixel using 24-bit ANSI colors */
+    Color,
+}
+
+impl RenderStyle {
+    /**Returns the style with the given name (ascii, blocks or color) */
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ascii" => Some(Self::Ascii),
+            "blocks" => Some(Self::Blocks),
+            "color" => Some(Self::Color),
+            _ => None,
+        }
+    }
+}
+
+/**Draws a square image as lines of text, which all take up the same width on screen.
+ * image - The pixel values between 0 and 1 (eg. a row of x_test)
+*/
+pub fn render_image(image: &Array1<f64>, style: RenderStyle) -> Vec<String> {
+    let side = (image.len() as f64).sqrt() as usize;
+    assert_eq!(side * side, image.len(), "images must be square");
+    let pixel = |row: usize, col: usize| {
+        if row < side {
+            image[row * side + col].clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    };
+    match style {
+        RenderStyle::Ascii => (0..side)
+            .map(|row| {
+                (0..side)
+                    .flat_map(|col| [ramp(ASCII_RAMP, pixel(row, col)); 2])
+                    .collect()
+            })
+            .collect(),
+        RenderStyle::Blocks => (0..side)
+            .step_by(2)
+            .map(|row| {
+                (0..side)
+                    .map(|col| {
+                        let top = pixel(row, col) >= BLOCK_THRESHOLD;
+                        let bottom = pixel(row + 1, col) >= BLOCK_THRESHOLD;
+                        match (top, bottom) {
+                            (true, true) => '█',
+                            (true, false) => '▀',
+                            (false, true) => '▄',
+                            (false, false) => ' ',
+                        }
+                    })
+                    .collect()
+            })
+            .collect(),
+        //the upper half block is drawn in the top pixel's gray over the bottom pixel's gray
+        RenderStyle::Color => (0..side)
+            .step_by(2)
+            .map(|row| {
+                let mut line: String = (0..side)
+                    .map(|col| {
+                        let (top, bottom) = (gray(pixel(row, col)), gray(pixel(row + 1, col)));
+                        format!("\x1b[38;2;{0};{0};{0}m\x1b[48;2;{1};{1};{1}m▀", top, bottom)
+                    })
+                    .collect();
+                line.push_str(RESET);
+                line
+            })
+            .collect(),
+    }
+}
+
+/**Draws a layer's activations as a strip with one cell per neuron, from darkest for
+ * the layer's lowest value to brightest for its highest, so any activation function's
+ * range can be shown.
+ * values - The activations of the layer (eg. a column of the hidden layer)
+*/
+pub fn render_activations(values: &Array1<f64>, style: RenderStyle) -> String {
+    let low = values.fold(f64::INFINITY, |m, &v| m.min(v));
+    let high = values.fold(f64::NEG_INFINITY, |m, &v| m.max(v));
+    let scaled = values.mapv(|v| {
+        if high - low > 0.0 {
+            (v - low) / (high - low)
+        } else {
+            0.0
+        }
+    });
+    match style {
+        RenderStyle::Ascii => scaled.iter().map(|&v| ramp(ASCII_RAMP, v)).collect(),
+        RenderStyle::Blocks => scaled.iter().map(|&v| ramp(SHADE_RAMP, v)).collect(),
+        RenderStyle::Color => {
+            let mut strip: String = scaled
+                .iter()
+                .map(|&v| format!("\x1b[48;2;{0};{0};{0}m ", gray(v)))
+                .collect();
+            strip.push_str(RESET);
+            strip
+        }
+    }
+}
+
+/*Picks the character of a ramp for a brightness between 0 and 1*/
+fn ramp(chars: &[char], value: f64) -> char {
+    chars[((value * chars.len() as f64) as usize).min(chars.len() - 1)]
+}
+
+/*Converts a brightness between 0 and 1 to an 8-bit gray*/
+fn gray(value: f64) -> u8 {
+    (value * 255.0).round() as u8
+}
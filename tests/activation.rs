@@ -0,0 +1,101 @@
+use ndarray::{array, Array2};
+use neural::networks::activation::{activation_from_name, log_softmax, softmax, OutputActivation};
+
+const NAMES: [&str; 9] = [
+    "sigmoid",
+    "tanh",
+    "relu",
+    "leaky-relu:0.1",
+    "elu:1",
+    "selu",
+    "gelu",
+    "swish",
+    "softplus",
+];
+
+/*Weighted sums spread over the range the activations are used in, avoiding 0 where
+ * the ReLU variants have no derivative*/
+fn sums() -> Array2<f64> {
+    Array2::from_shape_fn((41, 1), |(i, _)| i as f64 * 0.25 - 5.03)
+}
+
+#[test]
+fn activations_are_chosen_by_name() {
+    for name in NAMES {
+        let activation = activation_from_name(name).unwrap();
+        assert_eq!(activation.name(), name);
+    }
+    assert_eq!(
+        activation_from_name("leaky-relu").unwrap().name(),
+        "leaky-relu:0.01"
+    );
+    assert!(activation_from_name("sigmoid:2").is_none());
+    assert!(activation_from_name("leaky-relu:x").is_none());
+    assert!(activation_from_name("leaky-relu:nan").is_none());
+    assert!(activation_from_name("elu:inf").is_none());
+    assert!(activation_from_name("elu:-inf").is_none());
+    assert!(activation_from_name("step").is_none());
+}
+
+#[test]
+fn derivatives_match_finite_differences() {
+    let step = 1e-6;
+    let x = sums();
+    for name in NAMES {
+        let activation = activation_from_name(name).unwrap();
+        let numeric =
+            (activation.forward(&(&x + step)) - activation.forward(&(&x - step))) / (2.0 * step);
+        let analytic = activation.derivative(&x);
+        for (n, a) in numeric.iter().zip(analytic.iter()) {
+            assert!((n - a).abs() < 1e-5, "{}: {} != {}", name, n, a);
+        }
+    }
+}
+
+#[test]
+fn softplus_does_not_overflow() {
+    let softplus = activation_from_name("softplus").unwrap();
+    let x = Array2::from_elem((1, 1), 1000.0);
+    assert_eq!(softplus.forward(&x)[[0, 0]], 1000.0);
+}
+
+#[test]
+fn output_activations_are_chosen_by_name() {
+    for name in ["softmax", "sigmoid", "identity"] {
+        assert_eq!(OutputActivation::from_name(name).unwrap().name(), name);
+    }
+    assert!(OutputActivation::from_name("tanh").is_none());
+}
+
+#[test]
+fn output_activations_default_to_a_matching_loss() {
+    for (output, loss) in [
+        ("softmax", "cross-entropy"),
+        ("sigmoid", "binary-cross-entropy"),
+        ("identity", "mse"),
+    ] {
+        let output = OutputActivation::from_name(output).unwrap();
+        assert_eq!(output.default_loss().name(), loss);
+    }
+}
+
+#[test]
+fn softmax_gives_probabilities_for_each_column() {
+    let x = array![[1.0, -2.0], [2.0, 0.0], [3.0, 5.0]];
+    let s = softmax(&x);
+    for (column, probs) in x.columns().into_iter().zip(s.columns()) {
+        let total: f64 = column.iter().map(|v| v.exp()).sum();
+        for (v, p) in column.iter().zip(probs) {
+            assert!((v.exp() / total - p).abs() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn softmax_does_not_overflow() {
+    let x = array![[1000.0], [999.0], [-1000.0]];
+    let s = softmax(&x);
+    assert!(s.iter().all(|p| p.is_finite()));
+    assert!((s.sum() - 1.0).abs() < 1e-12);
+    assert!((log_softmax(&x)[[1, 0]] - (-1.0 - (-1f64).exp().ln_1p())).abs() < 1e-12);
+}
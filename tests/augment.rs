@@ -0,0 +1,53 @@
+use ndarray::Array1;
+use ndarray_rand::rand::rngs::StdRng;
+use ndarray_rand::rand::SeedableRng;
+use neural::augment::Augmentation;
+use neural::synthetic::SyntheticDigits;
+
+/*A few synthetic digits to change*/
+fn images() -> Vec<Array1<f64>> {
+    let digits = SyntheticDigits::generate(5, 1);
+    digits
+        .images()
+        .rows()
+        .into_iter()
+        .map(|r| r.to_owned())
+        .collect()
+}
+
+/*Applies an augmentation to every image with an rng seeded from its seed*/
+fn augmented(augmentation: &Augmentation) -> Vec<Array1<f64>> {
+    let mut rng = StdRng::seed_from_u64(augmentation.seed);
+    images()
+        .iter()
+        .map(|img| augmentation.apply(img, &mut rng))
+        .collect()
+}
+
+#[test]
+fn same_seed_gives_the_same_images() {
+    let augmentation = Augmentation::digits(3);
+    assert_eq!(augmented(&augmentation), augmented(&augmentation));
+    assert_ne!(
+        augmented(&augmentation),
+        augmented(&Augmentation::digits(4))
+    );
+}
+
+#[test]
+fn default_changes_nothing() {
+    for (before, after) in images().iter().zip(augmented(&Augmentation::default())) {
+        assert!((before - &after).iter().all(|d| d.abs() < 1e-12));
+    }
+}
+
+#[test]
+fn images_keep_their_shape_and_range() {
+    for seed in 0..5 {
+        for (before, after) in images().iter().zip(augmented(&Augmentation::digits(seed))) {
+            assert_eq!(after.len(), before.len());
+            assert!(after.iter().all(|v| (0.0..=1.0).contains(v)));
+            assert_ne!(&after, before);
+        }
+    }
+}
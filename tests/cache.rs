@@ -0,0 +1,72 @@
+use neural::cache::{cache_path, read_cached};
+use neural::read_data::DataSource;
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+
+//a label and 2 pixels per row
+const FIRST: &str = "label,a,b\n1,0,255\n2,10,20\n";
+//the same size as FIRST, so only the modification time tells them apart
+const SECOND: &str = "label,a,b\n3,0,255\n4,10,20\n";
+
+/*Writes a csv source file with a fixed modification time*/
+fn write_source(path: &Path, contents: &str, modified: SystemTime) {
+    fs::write(path, contents).unwrap();
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+}
+
+fn first_labels(source: &DataSource) -> Vec<u8> {
+    read_cached(source).unwrap().column(0).to_vec()
+}
+
+#[test]
+fn cache_round_trips_the_source() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("train.csv");
+    write_source(&path, FIRST, SystemTime::UNIX_EPOCH);
+    let source = DataSource::Csv(path);
+    let data = source.read().unwrap();
+    assert_eq!(read_cached(&source).unwrap(), data);
+    assert!(cache_path(&source).exists());
+    assert_eq!(read_cached(&source).unwrap(), data);
+}
+
+#[test]
+fn cache_is_used_until_the_source_changes() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("train.csv");
+    let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+    write_source(&path, FIRST, time);
+    let source = DataSource::Csv(path.clone());
+    assert_eq!(first_labels(&source), [1, 2]);
+    //the same size and time, so the cache is still believed to be current
+    write_source(&path, SECOND, time);
+    assert_eq!(first_labels(&source), [1, 2]);
+    //a new modification time rebuilds it
+    write_source(&path, SECOND, time + Duration::from_secs(1));
+    assert_eq!(first_labels(&source), [3, 4]);
+}
+
+#[test]
+fn corrupt_cache_is_ignored() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("train.csv");
+    write_source(&path, FIRST, SystemTime::UNIX_EPOCH);
+    let source = DataSource::Csv(path.clone());
+    read_cached(&source).unwrap();
+    //changing the source without changing its fingerprint, then damaging the cache's body
+    write_source(&path, SECOND, SystemTime::UNIX_EPOCH);
+    let mut cache = fs::read(cache_path(&source)).unwrap();
+    let last = cache.len() - 1;
+    cache[last] ^= 0xff;
+    fs::write(cache_path(&source), cache).unwrap();
+    //the checksum no longer matches, so the source is read again
+    assert_eq!(first_labels(&source), [3, 4]);
+    assert_eq!(read_cached(&source).unwrap(), source.read().unwrap());
+}
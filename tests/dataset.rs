@@ -0,0 +1,162 @@
+use ndarray::{array, Array1};
+use neural::dataset::{train_validation_split, Dataset, MnistDataset, Subset, ValidationSize};
+use neural::read_data::{get_testing_data, get_training_data, DataSource, DatasetKind};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const PIXELS: usize = 784;
+
+/*A data set of 80 samples of class 0 followed by 20 of class 1, whose input is its index*/
+struct Imbalanced;
+
+impl Dataset for Imbalanced {
+    fn len(&self) -> usize {
+        100
+    }
+    fn input_dim(&self) -> usize {
+        1
+    }
+    fn num_classes(&self) -> usize {
+        2
+    }
+    fn get(&self, i: usize) -> (Array1<f64>, usize) {
+        (array![i as f64], (i >= 80) as usize)
+    }
+}
+
+/*A data set of the given number of samples, spread evenly over 10 classes*/
+struct Balanced(usize);
+
+impl Dataset for Balanced {
+    fn len(&self) -> usize {
+        self.0
+    }
+    fn input_dim(&self) -> usize {
+        1
+    }
+    fn num_classes(&self) -> usize {
+        10
+    }
+    fn get(&self, i: usize) -> (Array1<f64>, usize) {
+        (array![i as f64], i % 10)
+    }
+}
+
+/*How many samples of each class a subset holds*/
+fn class_counts(subset: &Subset<Imbalanced>) -> [usize; 2] {
+    let mut counts = [0; 2];
+    for i in 0..subset.len() {
+        counts[subset.class_of(i)] += 1;
+    }
+    counts
+}
+
+#[test]
+fn split_covers_every_sample_once() {
+    let (train, validation) =
+        train_validation_split(&Imbalanced, ValidationSize::Fraction(0.3), false, 1);
+    assert_eq!((train.len(), validation.len()), (70, 30));
+    let mut all: Vec<usize> = [train.indices(), validation.indices()].concat();
+    all.sort();
+    assert_eq!(all, (0..100).collect::<Vec<_>>());
+}
+
+#[test]
+fn stratified_split_keeps_the_class_proportions() {
+    let (train, validation) =
+        train_validation_split(&Imbalanced, ValidationSize::Fraction(0.25), true, 1);
+    assert_eq!(class_counts(&validation), [20, 5]);
+    assert_eq!(class_counts(&train), [60, 15]);
+}
+
+#[test]
+fn exact_counts_are_held_out_without_stratification() {
+    let (train, validation) =
+        train_validation_split(&Imbalanced, ValidationSize::Count(7), false, 1);
+    assert_eq!((train.len(), validation.len()), (93, 7));
+}
+
+#[test]
+fn stratified_split_holds_out_exactly_the_count() {
+    for n in [0, 3, 7, 15, 20] {
+        let (train, validation) =
+            train_validation_split(&Balanced(20), ValidationSize::Count(n), true, 1);
+        assert_eq!((train.len(), validation.len()), (20 - n, n));
+    }
+    //the classes of 80 and 20 samples hold out 26.4 and 6.6, rounded by the larger remainder
+    let (_, validation) = train_validation_split(&Imbalanced, ValidationSize::Count(33), true, 1);
+    assert_eq!(class_counts(&validation), [26, 7]);
+}
+
+#[test]
+fn stratified_split_holds_out_the_rounded_fraction() {
+    for (fraction, expected) in [(0.1, 2), (0.33, 8), (0.5, 12)] {
+        let (_, validation) =
+            train_validation_split(&Balanced(23), ValidationSize::Fraction(fraction), true, 1);
+        assert_eq!(validation.len(), expected, "{}", fraction);
+    }
+}
+
+#[test]
+fn empty_data_sets_give_empty_splits() {
+    for size in [ValidationSize::Count(0), ValidationSize::Fraction(0.2)] {
+        let (train, validation) = train_validation_split(&Balanced(0), size, true, 1);
+        assert!(train.is_empty() && validation.is_empty());
+    }
+}
+
+#[test]
+fn split_is_the_same_for_the_same_seed() {
+    let split = |seed| {
+        let (train, validation) =
+            train_validation_split(&Imbalanced, ValidationSize::Fraction(0.2), true, seed);
+        (train.indices().to_vec(), validation.indices().to_vec())
+    };
+    assert_eq!(split(5), split(5));
+    assert_ne!(split(5), split(6));
+}
+
+#[test]
+#[should_panic(expected = "cannot hold out more samples")]
+fn split_cannot_hold_out_more_than_the_data_set() {
+    train_validation_split(&Imbalanced, ValidationSize::Count(101), false, 1);
+}
+
+/*Writes an IDX image file and label file of 28x28 images into a directory,
+ * named like the MNIST training split*/
+fn write_mnist(dir: &Path, labels: &[u8]) {
+    let count = labels.len() as u32;
+    let mut images = [0x0000_0803, count, 28, 28]
+        .iter()
+        .flat_map(|v: &u32| v.to_be_bytes())
+        .collect::<Vec<u8>>();
+    //every possible byte value appears in the pixels
+    images.extend((0..labels.len() * PIXELS).map(|i| (i * 7 % 256) as u8));
+    fs::write(dir.join("train-images-idx3-ubyte"), images).unwrap();
+    let mut label_file = [0x0000_0801, count]
+        .iter()
+        .flat_map(|v: &u32| v.to_be_bytes())
+        .collect::<Vec<u8>>();
+    label_file.extend_from_slice(labels);
+    fs::write(dir.join("train-labels-idx1-ubyte"), label_file).unwrap();
+}
+
+#[test]
+fn byte_samples_match_the_float_arrays() {
+    let dir = TempDir::new().unwrap();
+    write_mnist(dir.path(), &[3, 0, 9, 5]);
+    let source = DataSource::training(dir.path(), DatasetKind::Mnist);
+    let data = MnistDataset::load(&source, DatasetKind::Mnist, None).unwrap();
+    let (x, one_hot) = get_training_data(&source, DatasetKind::Mnist, 4).unwrap();
+    let (_, y) = get_testing_data(&source, DatasetKind::Mnist).unwrap();
+    assert_eq!(data.len(), 4);
+    for i in 0..data.len() {
+        let (input, class) = data.get(i);
+        assert_eq!(input, x.row(i));
+        assert_eq!(class as f64, y[i]);
+        assert_eq!(one_hot[[i, class]], 1.0);
+        assert_eq!(data.class_of(i), class);
+    }
+    assert_eq!(data.to_arrays(), (x, one_hot));
+}
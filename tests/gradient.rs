@@ -0,0 +1,122 @@
+use ndarray::Array2;
+use ndarray_rand::rand_distr::Uniform;
+use ndarray_rand::RandomExt;
+use neural::networks::{
+    activation::{activation_from_name, Activation, Gelu, OutputActivation, Tanh},
+    loss::{loss_from_name, CrossEntropy, Focal, MeanSquaredError},
+    mlp::Mlp,
+    neural_network::NeuralNetwork,
+    relu_network::ReluNetwork,
+    sigmoid_network::SigmoidNetwork,
+    tanh_network::TanhNetwork,
+};
+
+//a small network keeps the number of finite differences low
+const INPUTS: usize = 6;
+const HIDDEN: usize = 5;
+const OUTPUTS: usize = 3;
+//several samples are propagated at once, whose gradients back_propagation averages
+const BATCH: usize = 4;
+const STEP: f64 = 1e-6;
+const TOLERANCE: f64 = 1e-6;
+
+/*Compares the gradients found by the network with finite differences of its mean loss
+ * over a batch, for the weights and biases of every layer*/
+fn check_gradients<N: NeuralNetwork>(net: &mut N) {
+    let img = Array2::random((INPUTS, BATCH), Uniform::new(0.0, 1.0));
+    let lab = Array2::from_shape_fn((OUTPUTS, BATCH), |(i, j)| (i == j % OUTPUTS) as u8 as f64);
+    let saved = net.parameters();
+    net.forward_propagation(&img);
+    let analytic = net.gradients(&img, &lab);
+    assert_eq!(analytic.len(), saved.len());
+
+    for (i, analytic) in analytic.iter().enumerate() {
+        assert_eq!(analytic.dim(), saved[i].dim());
+        for (idx, expected) in analytic.indexed_iter() {
+            let mut loss_at = |offset: f64| {
+                let mut changed = saved.clone();
+                changed[i][idx] += offset;
+                net.set_parameters(changed);
+                net.forward_propagation(&img);
+                net.get_loss().value(&net.get_out_layer(), &lab) / BATCH as f64
+            };
+            let numeric = (loss_at(STEP) - loss_at(-STEP)) / (2.0 * STEP);
+            net.set_parameters(saved.clone());
+            assert!(
+                (numeric - expected).abs() < TOLERANCE * (1.0 + numeric.abs()),
+                "parameter {}{:?}: back propagation gave {}, finite differences {}",
+                i,
+                idx,
+                expected,
+                numeric
+            );
+        }
+    }
+}
+
+#[test]
+fn sigmoid_network_gradients_are_correct() {
+    check_gradients(&mut SigmoidNetwork::with_sizes(INPUTS, HIDDEN, OUTPUTS));
+}
+
+#[test]
+fn tanh_network_gradients_are_correct() {
+    check_gradients(&mut TanhNetwork::with_sizes(INPUTS, HIDDEN, OUTPUTS));
+}
+
+#[test]
+fn relu_network_gradients_are_correct() {
+    check_gradients(&mut ReluNetwork::with_sizes(INPUTS, HIDDEN, OUTPUTS));
+}
+
+#[test]
+fn gradients_are_correct_for_every_hidden_activation() {
+    for name in [
+        "sigmoid", "tanh", "elu:1", "selu", "gelu", "swish", "softplus",
+    ] {
+        let mut net = SigmoidNetwork::with_sizes(INPUTS, HIDDEN, OUTPUTS);
+        net.set_hid_activation(activation_from_name(name).unwrap());
+        assert_eq!(net.get_hid_activation().name(), name);
+        check_gradients(&mut net);
+    }
+}
+
+#[test]
+fn mlp_gradients_are_correct() {
+    let activations: Vec<Box<dyn Activation>> = vec![Box::new(Tanh)];
+    check_gradients(&mut Mlp::with_activations(
+        &[INPUTS, HIDDEN, OUTPUTS],
+        activations,
+    ));
+}
+
+#[test]
+fn deep_mlp_gradients_are_correct() {
+    let activations: Vec<Box<dyn Activation>> = vec![Box::new(Tanh), Box::new(Gelu)];
+    let mut net = Mlp::with_activations(&[INPUTS, HIDDEN, 4, OUTPUTS], activations);
+    net.set_output_activation(OutputActivation::Softmax);
+    net.set_loss(Box::new(CrossEntropy));
+    check_gradients(&mut net);
+}
+
+#[test]
+fn gradients_are_correct_for_every_loss() {
+    for name in ["cross-entropy", "mse", "hinge", "focal:1.5"] {
+        let mut net = SigmoidNetwork::with_sizes(INPUTS, HIDDEN, OUTPUTS);
+        net.set_loss(loss_from_name(name).unwrap());
+        check_gradients(&mut net);
+    }
+    let mut net = ReluNetwork::with_sizes(INPUTS, HIDDEN, OUTPUTS);
+    net.set_loss(Box::new(Focal { gamma: 2.0 }));
+    check_gradients(&mut net);
+}
+
+#[test]
+fn gradients_are_correct_for_every_output_activation() {
+    for output in ["softmax", "sigmoid", "identity"] {
+        let mut net = TanhNetwork::with_sizes(INPUTS, HIDDEN, OUTPUTS);
+        net.set_output_activation(OutputActivation::from_name(output).unwrap());
+        net.set_loss(Box::new(MeanSquaredError));
+        check_gradients(&mut net);
+    }
+}
@@ -0,0 +1,110 @@
+use ndarray::Array2;
+use neural::grid::{save_grid, GridOptions};
+use neural::read_data::DataError;
+use raster::Color;
+use std::path::Path;
+use tempfile::TempDir;
+
+//4x4 images, so every cell is 4 * SCALE + 4 pixels wide, and 7 + 4 pixels more in height
+//for its label
+const SIDE: usize = 4;
+const SCALE: usize = 2;
+const CELL_WIDTH: usize = SIDE * SCALE + 4;
+const CELL_HEIGHT: usize = CELL_WIDTH + 11;
+
+fn images(count: usize) -> Array2<f64> {
+    Array2::from_elem((count, SIDE * SIDE), 1.0)
+}
+
+fn pixel(path: &Path, x: usize, y: usize) -> (u8, u8, u8) {
+    let Color { r, g, b, .. } = raster::open(&path.to_string_lossy())
+        .unwrap()
+        .get_pixel(x as i32, y as i32)
+        .unwrap();
+    (r, g, b)
+}
+
+#[test]
+fn grid_has_a_cell_for_every_image() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("grid.png");
+    let labels = [1, 2, 3];
+    let options = GridOptions {
+        columns: 2,
+        scale: SCALE,
+        labels: Some(&labels),
+        ..Default::default()
+    };
+    save_grid(&images(3), &path, &options).unwrap();
+    let grid = raster::open(&path.to_string_lossy()).unwrap();
+    assert_eq!(
+        (grid.width as usize, grid.height as usize),
+        (2 * CELL_WIDTH, 2 * CELL_HEIGHT)
+    );
+    //without labels the cells only hold the images
+    save_grid(&images(3), &path, &GridOptions::default()).unwrap();
+    let grid = raster::open(&path.to_string_lossy()).unwrap();
+    let cell = SIDE * 2 + 4;
+    assert_eq!(
+        (grid.width as usize, grid.height as usize),
+        (3 * cell, cell)
+    );
+}
+
+#[test]
+fn mistakes_have_a_red_border() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("grid.png");
+    let (labels, predictions) = ([1, 2], [1, 7]);
+    let options = GridOptions {
+        columns: 2,
+        scale: SCALE,
+        labels: Some(&labels),
+        predictions: Some(&predictions),
+        ..Default::default()
+    };
+    save_grid(&images(2), &path, &options).unwrap();
+    //the corner of each cell is part of its border
+    assert_eq!(pixel(&path, 0, 0), (64, 64, 64));
+    assert_eq!(pixel(&path, CELL_WIDTH, 0), (230, 40, 40));
+    //the image itself is drawn inside the border
+    assert_eq!(pixel(&path, CELL_WIDTH + 2, 2), (255, 255, 255));
+}
+
+#[test]
+fn labels_and_predictions_must_match_the_images() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("grid.png");
+    let short = [1];
+    for options in [
+        GridOptions {
+            labels: Some(&short),
+            ..Default::default()
+        },
+        GridOptions {
+            predictions: Some(&short),
+            ..Default::default()
+        },
+    ] {
+        assert!(matches!(
+            save_grid(&images(2), &path, &options),
+            Err(DataError::ShapeMismatch {
+                expected: 2,
+                found: 1,
+                ..
+            })
+        ));
+    }
+    assert!(!path.exists());
+}
+
+#[test]
+fn empty_grids_are_errors() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("grid.png");
+    assert!(matches!(
+        save_grid(&images(0), &path, &GridOptions::default()),
+        Err(DataError::NoSamples { .. })
+    ));
+    assert!(!path.exists());
+}
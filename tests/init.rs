@@ -0,0 +1,183 @@
+use ndarray::Array2;
+use ndarray_rand::rand::rngs::StdRng;
+use ndarray_rand::rand::SeedableRng;
+use neural::networks::{
+    activation::{Activation, Relu, Selu, Tanh},
+    init::Initializer,
+    mlp::Mlp,
+    neural_network::NeuralNetwork,
+    relu_network::ReluNetwork,
+};
+
+const NAMES: [&str; 10] = [
+    "xavier-uniform",
+    "xavier-normal",
+    "he-uniform",
+    "he-normal",
+    "lecun-uniform",
+    "lecun-normal",
+    "orthogonal",
+    "zeros",
+    "constant:0.1",
+    "uniform:0.5",
+];
+//large enough for the sample variance to be close to the expected one
+const FAN_OUT: usize = 200;
+const FAN_IN: usize = 300;
+
+fn rng() -> StdRng {
+    StdRng::seed_from_u64(1)
+}
+
+fn variance(m: &Array2<f64>) -> f64 {
+    m.mapv(|v| v * v).mean().unwrap()
+}
+
+fn max_abs(m: &Array2<f64>) -> f64 {
+    m.iter().fold(0.0, |max, v| v.abs().max(max))
+}
+
+#[test]
+fn initializers_are_chosen_by_name() {
+    for name in NAMES {
+        assert_eq!(Initializer::from_name(name).unwrap().name(), name);
+    }
+    assert_eq!(
+        Initializer::from_name("constant"),
+        Some(Initializer::Constant(0.0))
+    );
+    assert_eq!(
+        Initializer::from_name("uniform"),
+        Some(Initializer::Uniform(0.5))
+    );
+    assert!(Initializer::from_name("zeros:1").is_none());
+    assert!(Initializer::from_name("constant:x").is_none());
+    assert!(Initializer::from_name("kaiming").is_none());
+    assert_eq!(
+        Initializer::from_name("constant:-0.1"),
+        Some(Initializer::Constant(-0.1))
+    );
+    for name in [
+        "uniform:-0.5",
+        "uniform:inf",
+        "constant:nan",
+        "constant:-inf",
+    ] {
+        assert!(Initializer::from_name(name).is_none(), "{}", name);
+    }
+}
+
+#[test]
+fn random_initializers_are_scaled_by_the_layer_size() {
+    let (fan_in, fan_out) = (FAN_IN as f64, FAN_OUT as f64);
+    //the variance of each, and the limit of the uniform ones
+    let expected = [
+        (Initializer::XavierUniform, 2.0 / (fan_in + fan_out), true),
+        (Initializer::XavierNormal, 2.0 / (fan_in + fan_out), false),
+        (Initializer::HeUniform, 2.0 / fan_in, true),
+        (Initializer::HeNormal, 2.0 / fan_in, false),
+        (Initializer::LecunUniform, 1.0 / fan_in, true),
+        (Initializer::LecunNormal, 1.0 / fan_in, false),
+    ];
+    for (init, var, uniform) in expected {
+        let m = init.init((FAN_OUT, FAN_IN), &mut rng());
+        assert_eq!(m.dim(), (FAN_OUT, FAN_IN));
+        let ratio = variance(&m) / var;
+        assert!(
+            (0.95..1.05).contains(&ratio),
+            "{:?} variance ratio {}",
+            init,
+            ratio
+        );
+        if uniform {
+            //a uniform distribution within ±limit has a variance of limit^2 / 3
+            assert!(max_abs(&m) <= (3.0 * var).sqrt(), "{:?}", init);
+        }
+    }
+    assert!(max_abs(&Initializer::Uniform(0.2).init((FAN_OUT, FAN_IN), &mut rng())) <= 0.2);
+}
+
+#[test]
+fn orthogonal_rows_or_columns_are_orthonormal() {
+    for shape in [(5, 8), (8, 5), (6, 6)] {
+        let m = Initializer::Orthogonal.init(shape, &mut rng());
+        assert_eq!(m.dim(), shape);
+        //the smaller of m * m^T and m^T * m is the identity
+        let gram = if shape.0 <= shape.1 {
+            m.dot(&m.t())
+        } else {
+            m.t().dot(&m)
+        };
+        let identity = Array2::<f64>::eye(gram.nrows());
+        assert!(
+            (gram - identity).iter().all(|v| v.abs() < 1e-10),
+            "{:?}",
+            shape
+        );
+    }
+}
+
+#[test]
+fn zeros_and_constant_fill_every_value() {
+    assert_eq!(
+        Initializer::Zeros.init((3, 1), &mut rng()),
+        Array2::zeros((3, 1))
+    );
+    assert_eq!(
+        Initializer::Constant(0.1).init((2, 4), &mut rng()),
+        Array2::from_elem((2, 4), 0.1)
+    );
+}
+
+#[test]
+fn the_same_seed_gives_the_same_values() {
+    for init in [Initializer::HeNormal, Initializer::Orthogonal] {
+        let m = init.init((4, 3), &mut rng());
+        assert_eq!(init.init((4, 3), &mut rng()), m);
+        assert_ne!(init.init((4, 3), &mut StdRng::seed_from_u64(2)), m);
+    }
+    let net = ReluNetwork::with_seed(6, 5, 3, 7);
+    assert_eq!(
+        ReluNetwork::with_seed(6, 5, 3, 7).parameters(),
+        net.parameters()
+    );
+    assert_ne!(
+        ReluNetwork::with_seed(6, 5, 3, 8).parameters(),
+        net.parameters()
+    );
+    let activations = || vec![Box::new(Relu) as Box<dyn Activation>];
+    let mlp = Mlp::with_seed(&[6, 5, 3], activations(), 7);
+    assert_eq!(
+        Mlp::with_seed(&[6, 5, 3], activations(), 7).parameters(),
+        mlp.parameters()
+    );
+}
+
+#[test]
+fn defaults_suit_the_activation() {
+    assert_eq!(Tanh.initializer(), Initializer::XavierUniform);
+    assert_eq!(Relu.initializer(), Initializer::HeUniform);
+    assert_eq!(Selu.initializer(), Initializer::LecunNormal);
+}
+
+#[test]
+fn initialize_sets_every_layer() {
+    let mut net = Mlp::new(&[FAN_IN, FAN_OUT, 10]);
+    net.initialize(
+        &[Initializer::HeNormal, Initializer::Zeros],
+        Initializer::Constant(0.1),
+        &mut rng(),
+    );
+    let params = net.parameters();
+    let ratio = variance(&params[0]) * FAN_IN as f64 / 2.0;
+    assert!((0.95..1.05).contains(&ratio), "variance ratio {}", ratio);
+    assert_eq!(params[1], Array2::from_elem((FAN_OUT, 1), 0.1));
+    assert_eq!(params[2], Array2::zeros((10, FAN_OUT)));
+    assert_eq!(params[3], Array2::from_elem((10, 1), 0.1));
+}
+
+#[test]
+#[should_panic(expected = "every layer needs an initializer")]
+fn initialize_needs_an_initializer_per_layer() {
+    Mlp::new(&[4, 3, 2]).initialize(&[Initializer::HeNormal], Initializer::Zeros, &mut rng());
+}
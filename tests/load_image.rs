@@ -0,0 +1,100 @@
+use ndarray::{Array1, Array2};
+use neural::read_data::{load_image, DataError};
+use raster::{Color, Image};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/*Saves a png of the given size with one rectangle of ink, from its top left corner
+ * (row, col) to its size (height, width)*/
+fn rectangle_png(
+    dir: &Path,
+    name: &str,
+    size: (i32, i32),
+    rect: (i32, i32, i32, i32),
+    dark_on_light: bool,
+) -> PathBuf {
+    let (paper, ink) = if dark_on_light {
+        (Color::white(), Color::black())
+    } else {
+        (Color::black(), Color::white())
+    };
+    let mut image = Image::blank(size.1, size.0);
+    let (top, left, height, width) = rect;
+    for r in 0..size.0 {
+        for c in 0..size.1 {
+            let inside = (top..top + height).contains(&r) && (left..left + width).contains(&c);
+            let color = if inside { ink.clone() } else { paper.clone() };
+            image.set_pixel(c, r, color).unwrap();
+        }
+    }
+    let path = dir.join(name);
+    raster::save(&image, &path.to_string_lossy()).unwrap();
+    path
+}
+
+/*The rows and columns holding any ink*/
+fn extent(img: &Array1<f64>) -> ((usize, usize), (usize, usize)) {
+    let img = img.to_shape((28, 28)).unwrap();
+    let (mut rows, mut cols) = ((28, 0), (28, 0));
+    for ((r, c), &v) in img.indexed_iter() {
+        if v > 0.5 {
+            rows = (rows.0.min(r), rows.1.max(r));
+            cols = (cols.0.min(c), cols.1.max(c));
+        }
+    }
+    (rows, cols)
+}
+
+fn center_of_mass(img: &Array1<f64>) -> (f64, f64) {
+    let img: Array2<f64> = img.to_shape((28, 28)).unwrap().to_owned();
+    let (mut y, mut x) = (0.0, 0.0);
+    for ((r, c), &v) in img.indexed_iter() {
+        y += r as f64 * v;
+        x += c as f64 * v;
+    }
+    (y / img.sum(), x / img.sum())
+}
+
+#[test]
+fn characters_are_cropped_scaled_and_centered() {
+    let dir = TempDir::new().unwrap();
+    //a 20x40 block well off the middle of a 60x100 image
+    let path = rectangle_png(dir.path(), "block.png", (60, 100), (5, 50, 20, 40), true);
+    let img = load_image(&path).unwrap();
+    assert_eq!(img.len(), 784);
+    assert!(img.iter().all(|v| (0.0..=1.0).contains(v)));
+    //the longer side becomes 20 pixels, keeping the proportions
+    let ((top, bottom), (left, right)) = extent(&img);
+    assert_eq!((bottom - top + 1, right - left + 1), (10, 20));
+    let (y, x) = center_of_mass(&img);
+    assert!(
+        (y - 13.5).abs() <= 0.5 && (x - 13.5).abs() <= 0.5,
+        "{} {}",
+        y,
+        x
+    );
+}
+
+#[test]
+fn light_and_dark_backgrounds_give_the_same_image() {
+    let dir = TempDir::new().unwrap();
+    let rect = (10, 10, 30, 12);
+    let dark = load_image(&rectangle_png(dir.path(), "dark.png", (50, 50), rect, true)).unwrap();
+    let light = load_image(&rectangle_png(
+        dir.path(),
+        "light.png",
+        (50, 50),
+        rect,
+        false,
+    ))
+    .unwrap();
+    assert!((dark - light).iter().all(|v| v.abs() < 1e-9));
+}
+
+#[test]
+fn blank_and_missing_images_are_errors() {
+    let dir = TempDir::new().unwrap();
+    let blank = rectangle_png(dir.path(), "blank.png", (20, 20), (0, 0, 0, 0), true);
+    assert!(matches!(load_image(&blank), Err(DataError::Image { .. })));
+    assert!(load_image(&dir.path().join("missing.png")).is_err());
+}
@@ -0,0 +1,140 @@
+use ndarray::{array, concatenate, Array2, Axis};
+use neural::networks::{
+    activation::OutputActivation,
+    loss::{loss_from_name, BinaryCrossEntropy, CrossEntropy, Loss},
+};
+
+const NAMES: [&str; 5] = [
+    "cross-entropy",
+    "binary-cross-entropy",
+    "mse",
+    "hinge",
+    "focal:1.5",
+];
+
+/*An output layer between 0 and 1 and a one hot label, with no output on a kink of hinge*/
+fn sample() -> (Array2<f64>, Array2<f64>) {
+    (
+        array![[0.2], [0.7], [0.45], [0.05]],
+        array![[0.0], [1.0], [0.0], [0.0]],
+    )
+}
+
+#[test]
+fn losses_are_chosen_by_name() {
+    for name in NAMES {
+        let loss = loss_from_name(name).unwrap();
+        assert_eq!(loss.name(), name);
+    }
+    assert_eq!(loss_from_name("focal").unwrap().name(), "focal:2");
+    assert!(loss_from_name("mse:2").is_none());
+    assert!(loss_from_name("focal:x").is_none());
+    for name in ["focal:nan", "focal:inf", "focal:-2"] {
+        assert!(loss_from_name(name).is_none(), "{}", name);
+    }
+    assert_eq!(loss_from_name("focal:0").unwrap().name(), "focal:0");
+    assert!(loss_from_name("l1").is_none());
+}
+
+#[test]
+fn gradients_match_finite_differences() {
+    let step = 1e-6;
+    let (out, lab) = sample();
+    for name in NAMES {
+        let loss = loss_from_name(name).unwrap();
+        let analytic = loss.gradient(&out, &lab);
+        for (idx, a) in analytic.indexed_iter() {
+            let value_at = |offset: f64| {
+                let mut changed = out.clone();
+                changed[idx] += offset;
+                loss.value(&changed, &lab)
+            };
+            let numeric = (value_at(step) - value_at(-step)) / (2.0 * step);
+            assert!((numeric - a).abs() < 1e-5, "{}: {} != {}", name, numeric, a);
+        }
+    }
+}
+
+#[test]
+fn losses_are_lowest_for_the_label() {
+    let (out, lab) = sample();
+    for name in NAMES {
+        let loss = loss_from_name(name).unwrap();
+        assert!(loss.value(&lab, &lab) < 1e-6, "{}", name);
+        assert!(loss.value(&out, &lab) > loss.value(&lab, &lab), "{}", name);
+    }
+}
+
+#[test]
+fn losses_stay_finite_for_saturated_outputs() {
+    let lab = array![[0.0], [1.0]];
+    let out = array![[1.0], [0.0]];
+    for name in NAMES {
+        let loss = loss_from_name(name).unwrap();
+        assert!(loss.value(&out, &lab).is_finite(), "{}", name);
+        assert!(
+            loss.gradient(&out, &lab).iter().all(|g| g.is_finite()),
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn saturated_wrong_outputs_keep_their_full_error() {
+    let assert_close = |a: &Array2<f64>, b: &Array2<f64>| {
+        assert!((a - b).iter().all(|v| v.abs() < 1e-9), "{} != {}", a, b)
+    };
+    //softmax of [30, 0] puts almost all of the probability on the wrong class
+    let lab = array![[0.0], [1.0]];
+    let out = OutputActivation::Softmax.forward(&array![[30.0], [0.0]]);
+    let error = CrossEntropy.output_error(OutputActivation::Softmax, &out, &lab);
+    assert_close(&error, &array![[1.0], [-1.0]]);
+    //sigmoid outputs that round to exactly 1 and 0
+    let out = OutputActivation::Sigmoid.forward(&array![[40.0], [-800.0]]);
+    assert_eq!(out, array![[1.0], [0.0]]);
+    let error = BinaryCrossEntropy.output_error(OutputActivation::Sigmoid, &out, &lab);
+    assert_close(&error, &array![[1.0], [-1.0]]);
+}
+
+#[test]
+fn output_errors_follow_the_chain_rule() {
+    let (out, lab) = sample();
+    for activation in [OutputActivation::Sigmoid, OutputActivation::Identity] {
+        for name in NAMES {
+            let loss = loss_from_name(name).unwrap();
+            let expected = activation.error(&out, &loss.gradient(&out, &lab));
+            let error = loss.output_error(activation, &out, &lab);
+            assert!(
+                (error - expected).iter().all(|v| v.abs() < 1e-9),
+                "{} with {}",
+                name,
+                activation.name()
+            );
+        }
+    }
+}
+
+#[test]
+fn losses_of_a_batch_add_up_the_samples() {
+    let (out, lab) = sample();
+    let other_out = array![[0.6], [0.1], [0.25], [0.3]];
+    let other_lab = array![[0.0], [0.0], [1.0], [0.0]];
+    let batch_out = concatenate![Axis(1), out, other_out];
+    let batch_lab = concatenate![Axis(1), lab, other_lab];
+    for name in NAMES {
+        let loss = loss_from_name(name).unwrap();
+        let total = loss.value(&out, &lab) + loss.value(&other_out, &other_lab);
+        assert!(
+            (loss.value(&batch_out, &batch_lab) - total).abs() < 1e-12,
+            "{}",
+            name
+        );
+        let gradient = loss.gradient(&batch_out, &batch_lab);
+        assert_eq!(gradient.column(0), loss.gradient(&out, &lab).column(0));
+        assert_eq!(
+            gradient.column(1),
+            loss.gradient(&other_out, &other_lab).column(0)
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use ndarray::{array, s, Array1, Array2, Axis};
+use neural::dataset::{Dataset, Subset};
+use neural::normalize::{NormalizeMode, Normalizer};
+use neural::read_data::DataError;
+use std::fs;
+use tempfile::TempDir;
+
+/*Three samples of two inputs, the second of which never changes*/
+struct Samples;
+
+impl Samples {
+    fn inputs() -> Array2<f64> {
+        array![[0.0, 1.0], [0.5, 1.0], [1.0, 1.0]]
+    }
+}
+
+impl Dataset for Samples {
+    fn len(&self) -> usize {
+        3
+    }
+    fn input_dim(&self) -> usize {
+        2
+    }
+    fn num_classes(&self) -> usize {
+        1
+    }
+    fn get(&self, i: usize) -> (Array1<f64>, usize) {
+        (Self::inputs().row(i).to_owned(), 0)
+    }
+}
+
+fn assert_close(a: &Array2<f64>, b: &Array2<f64>) {
+    assert!((a - b).iter().all(|v| v.abs() < 1e-12), "{} != {}", a, b);
+}
+
+#[test]
+fn modes_are_chosen_by_name() {
+    assert_eq!(
+        NormalizeMode::from_name("global"),
+        Some(NormalizeMode::Global)
+    );
+    assert_eq!(
+        NormalizeMode::from_name("Per-Pixel"),
+        Some(NormalizeMode::PerPixel)
+    );
+    assert_eq!(
+        NormalizeMode::from_name("min-max"),
+        Some(NormalizeMode::MinMax)
+    );
+    assert_eq!(NormalizeMode::from_name("zca"), None);
+}
+
+#[test]
+fn per_pixel_standardizes_each_input() {
+    let normalizer = Normalizer::fit(&Samples, NormalizeMode::PerPixel).unwrap();
+    let std = (1.0_f64 / 6.0).sqrt();
+    let expected = array![[-0.5 / std, 0.0], [0.0, 0.0], [0.5 / std, 0.0]];
+    assert_close(&normalizer.apply_rows(&Samples::inputs()), &expected);
+    //single inputs are normalized the same way as rows
+    assert_close(
+        &normalizer.apply(&array![1.0, 1.0]).insert_axis(Axis(0)),
+        &expected.slice(s![2..3, ..]).to_owned(),
+    );
+}
+
+#[test]
+fn global_standardizes_all_inputs_together() {
+    let normalizer = Normalizer::fit(&Samples, NormalizeMode::Global).unwrap();
+    let x = normalizer.apply_rows(&Samples::inputs());
+    assert!(x.mean().unwrap().abs() < 1e-12);
+    assert!((x.mapv(|v| v * v).mean().unwrap() - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn min_max_rescales_each_input_between_0_and_1() {
+    let normalizer = Normalizer::fit(&Samples, NormalizeMode::MinMax).unwrap();
+    //an input that never changes is only shifted
+    assert_close(
+        &normalizer.apply_rows(&Samples::inputs()),
+        &array![[0.0, 0.0], [0.5, 0.0], [1.0, 0.0]],
+    );
+}
+
+#[test]
+fn fitting_needs_samples() {
+    let empty = Subset::new(&Samples, Vec::new());
+    for mode in [
+        NormalizeMode::Global,
+        NormalizeMode::PerPixel,
+        NormalizeMode::MinMax,
+    ] {
+        assert!(matches!(
+            Normalizer::fit(&empty, mode),
+            Err(DataError::NoSamples { .. })
+        ));
+    }
+}
+
+#[test]
+fn normalizer_is_saved_and_loaded() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("normalizer.txt");
+    let normalizer = Normalizer::fit(&Samples, NormalizeMode::PerPixel).unwrap();
+    normalizer.save(&path).unwrap();
+    assert_eq!(Normalizer::load(&path).unwrap(), normalizer);
+    for contents in [
+        "0.5 1\n0.5\n",
+        "0.5 0\n",
+        "0.5 -1\n",
+        "0.5 inf\n",
+        "NaN 1\n",
+    ] {
+        fs::write(&path, contents).unwrap();
+        assert!(Normalizer::load(&path).is_err(), "{:?}", contents);
+    }
+}
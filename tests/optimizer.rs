@@ -0,0 +1,108 @@
+use ndarray::{array, Array2};
+use neural::networks::{
+    neural_network::NeuralNetwork,
+    optimizer::{optimizer_from_name, Adam, AdamW, Optimizer},
+    sigmoid_network::SigmoidNetwork,
+};
+
+const NAMES: [&str; 7] = [
+    "sgd",
+    "momentum:0.9",
+    "nesterov:0.9",
+    "rmsprop:0.9",
+    "adagrad",
+    "adam",
+    "adamw:0.01",
+];
+
+/*The gradient of the sum of (param - target)^2, a bowl every optimizer should descend*/
+fn bowl_gradient(param: &Array2<f64>, target: &Array2<f64>) -> Array2<f64> {
+    2.0 * (param - target)
+}
+
+#[test]
+fn optimizers_are_chosen_by_name() {
+    for name in NAMES {
+        assert_eq!(optimizer_from_name(name).unwrap().name(), name);
+    }
+    assert_eq!(
+        optimizer_from_name("momentum").unwrap().name(),
+        "momentum:0.9"
+    );
+    assert!(optimizer_from_name("adam:0.5").is_none());
+    assert!(optimizer_from_name("momentum:x").is_none());
+    assert!(optimizer_from_name("lbfgs").is_none());
+    for name in [
+        "momentum:1",
+        "momentum:-0.1",
+        "nesterov:nan",
+        "rmsprop:1.5",
+        "rmsprop:inf",
+        "adamw:-0.01",
+        "adamw:inf",
+        "adamw:nan",
+    ] {
+        assert!(optimizer_from_name(name).is_none(), "{}", name);
+    }
+    assert_eq!(
+        optimizer_from_name("momentum:0").unwrap().name(),
+        "momentum:0"
+    );
+    assert_eq!(optimizer_from_name("adamw:0").unwrap().name(), "adamw:0");
+}
+
+#[test]
+fn optimizers_descend_to_the_minimum() {
+    let target = array![[1.0, -2.0], [0.5, 3.0]];
+    //each optimizer with a learning rate that suits it
+    let rates = [0.05, 0.05, 0.05, 0.01, 0.5, 0.05, 0.05];
+    for (name, lr) in NAMES.into_iter().zip(rates) {
+        let mut optimizer = optimizer_from_name(name).unwrap();
+        //a second parameter checks that each keeps its own state
+        let mut params = [Array2::zeros((2, 2)), Array2::ones((3, 1))];
+        for _ in 0..1000 {
+            let grad = bowl_gradient(&params[0], &target);
+            optimizer.update(0, &mut params[0], &grad, lr);
+            let grad = bowl_gradient(&params[1], &Array2::zeros((3, 1)));
+            optimizer.update(1, &mut params[1], &grad, lr);
+        }
+        //weight decay keeps adamw slightly short of the minimum
+        let distance = (&params[0] - &target).mapv(f64::abs).sum();
+        assert!(
+            distance < 0.1,
+            "{} ended {} from the minimum",
+            name,
+            distance
+        );
+        assert!(params[1].iter().all(|p| p.abs() < 0.1), "{}", name);
+    }
+}
+
+#[test]
+fn adam_first_step_is_the_learning_rate() {
+    let mut param = array![[0.0, 0.0]];
+    Adam::default().update(0, &mut param, &array![[1e-3, -50.0]], 0.1);
+    assert!((param[[0, 0]] + 0.1).abs() < 1e-4);
+    assert!((param[[0, 1]] - 0.1).abs() < 1e-4);
+}
+
+#[test]
+fn adamw_decays_weights_without_gradients() {
+    let mut param = array![[2.0]];
+    AdamW::new(0.1).update(0, &mut param, &array![[0.0]], 0.5);
+    assert!((param[[0, 0]] - 2.0 * (1.0 - 0.5 * 0.1)).abs() < 1e-12);
+}
+
+#[test]
+fn back_propagation_applies_the_optimizer_to_the_gradients() {
+    let mut net = SigmoidNetwork::with_sizes(4, 3, 2);
+    let img = array![[0.1], [0.9], [0.4], [0.0]];
+    let lab = array![[0.0], [1.0]];
+    let before = net.parameters();
+    net.forward_propagation(&img);
+    let grads = net.gradients(&img, &lab);
+    net.back_propagation(&img, &lab, 0.5);
+    for ((before, after), grad) in before.iter().zip(net.parameters()).zip(grads) {
+        assert_eq!(after, before - 0.5 * grad);
+    }
+}
@@ -0,0 +1,246 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use ndarray::{array, Array1};
+use neural::read_data::{
+    check_labels, data_dir_from_env, one_hot_encode, one_hot_encode_smoothed, open_data, read_idx,
+    read_idx_images, read_idx_labels, DataError, DataSource, DatasetKind,
+};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+const LABEL_MAGIC: u32 = 0x0000_0801;
+const IMAGE_MAGIC: u32 = 0x0000_0803;
+
+/*Writes an IDX file with the given magic number, header dimensions and body*/
+fn idx_file(dir: &Path, name: &str, magic: u32, dims: &[u32], body: &[u8]) -> PathBuf {
+    let mut bytes = magic.to_be_bytes().to_vec();
+    for dim in dims {
+        bytes.extend_from_slice(&dim.to_be_bytes());
+    }
+    bytes.extend_from_slice(body);
+    let path = dir.join(name);
+    fs::write(&path, bytes).unwrap();
+    path
+}
+
+#[test]
+fn idx_images_and_labels_are_read_into_rows() {
+    let dir = TempDir::new().unwrap();
+    let images = idx_file(
+        dir.path(),
+        "images",
+        IMAGE_MAGIC,
+        &[2, 2, 2],
+        &[1, 2, 3, 4, 5, 6, 7, 8],
+    );
+    let labels = idx_file(dir.path(), "labels", LABEL_MAGIC, &[2], &[7, 3]);
+    assert_eq!(
+        read_idx_images(&images).unwrap(),
+        array![[1, 2, 3, 4], [5, 6, 7, 8]]
+    );
+    assert_eq!(read_idx_labels(&labels).unwrap(), array![7, 3]);
+    assert_eq!(
+        read_idx(&images, &labels).unwrap(),
+        array![[7, 1, 2, 3, 4], [3, 5, 6, 7, 8]]
+    );
+}
+
+#[test]
+fn idx_magic_numbers_are_checked() {
+    let dir = TempDir::new().unwrap();
+    let labels = idx_file(dir.path(), "labels", LABEL_MAGIC, &[1], &[0]);
+    assert!(matches!(
+        read_idx_images(&labels),
+        Err(DataError::Parse { .. })
+    ));
+    let images = idx_file(dir.path(), "images", IMAGE_MAGIC, &[1, 1, 1], &[0]);
+    assert!(matches!(
+        read_idx_labels(&images),
+        Err(DataError::Parse { .. })
+    ));
+}
+
+#[test]
+fn idx_body_must_match_the_header() {
+    let dir = TempDir::new().unwrap();
+    for body in [&[1, 2, 3][..], &[1, 2, 3, 4, 5]] {
+        let images = idx_file(dir.path(), "images", IMAGE_MAGIC, &[1, 2, 2], body);
+        match read_idx_images(&images) {
+            Err(DataError::ShapeMismatch {
+                expected, found, ..
+            }) => assert_eq!((expected, found), (4, body.len())),
+            other => panic!("expected a shape mismatch, got {:?}", other),
+        }
+    }
+    let empty = idx_file(dir.path(), "empty", IMAGE_MAGIC, &[1, 0, 28], &[]);
+    assert!(matches!(
+        read_idx_images(&empty),
+        Err(DataError::Parse { .. })
+    ));
+}
+
+#[test]
+fn idx_image_and_label_counts_must_agree() {
+    let dir = TempDir::new().unwrap();
+    let images = idx_file(dir.path(), "images", IMAGE_MAGIC, &[2, 1, 1], &[1, 2]);
+    let labels = idx_file(dir.path(), "labels", LABEL_MAGIC, &[3], &[0, 1, 2]);
+    match read_idx(&images, &labels) {
+        Err(DataError::ShapeMismatch {
+            expected, found, ..
+        }) => assert_eq!((expected, found), (3, 2)),
+        other => panic!("expected a shape mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn bad_idx_headers_are_errors_rather_than_crashes() {
+    let dir = TempDir::new().unwrap();
+    //the size of the body overflows
+    let overflowing = idx_file(dir.path(), "overflowing", IMAGE_MAGIC, &[u32::MAX; 3], &[]);
+    assert!(matches!(
+        read_idx_images(&overflowing),
+        Err(DataError::Parse { .. })
+    ));
+    //the size of the body fits, but is far too large to allocate
+    let oversized = idx_file(
+        dir.path(),
+        "oversized",
+        IMAGE_MAGIC,
+        &[u32::MAX, 28, 28],
+        &[0],
+    );
+    assert!(matches!(
+        read_idx_images(&oversized),
+        Err(DataError::ShapeMismatch { found: 1, .. })
+    ));
+    let labels = idx_file(dir.path(), "labels", LABEL_MAGIC, &[u32::MAX], &[0]);
+    assert!(matches!(
+        read_idx_labels(&labels),
+        Err(DataError::ShapeMismatch { found: 1, .. })
+    ));
+    //the header ends before its dimensions
+    let truncated = dir.path().join("truncated");
+    fs::write(&truncated, [0, 0, 8, 3, 0, 0]).unwrap();
+    assert!(matches!(
+        read_idx_images(&truncated),
+        Err(DataError::Io { .. })
+    ));
+}
+
+#[test]
+fn data_sets_sharing_file_names_have_their_own_directories() {
+    let kinds = [
+        DatasetKind::Mnist,
+        DatasetKind::FashionMnist,
+        DatasetKind::Kmnist,
+    ];
+    for (i, a) in kinds.iter().enumerate() {
+        for b in &kinds[i + 1..] {
+            assert_ne!(a.default_dir(), b.default_dir());
+        }
+    }
+    //the MNIST environment variable never points another data set at the MNIST files
+    assert_eq!(
+        data_dir_from_env(DatasetKind::FashionMnist),
+        Path::new("fashion_mnist_data")
+    );
+}
+
+#[test]
+fn labels_must_be_whole_numbers_within_the_classes() {
+    assert!(check_labels(&array![0.0, 9.0, 3.0], 10, None).is_ok());
+    for (label, index) in [(-1.0, 1), (2.5, 1), (10.0, 1), (f64::NAN, 1)] {
+        match check_labels(&array![0.0, label, 3.0], 10, Some(Path::new("labels"))) {
+            Err(DataError::LabelOutOfRange {
+                path,
+                index: found,
+                classes,
+                ..
+            }) => {
+                assert_eq!(found, index);
+                assert_eq!(classes, 10);
+                assert_eq!(path.as_deref(), Some(Path::new("labels")));
+            }
+            other => panic!("{} was accepted: {:?}", label, other),
+        }
+    }
+    assert!(matches!(
+        one_hot_encode(&array![1.0, 3.0], 3),
+        Err(DataError::LabelOutOfRange { index: 1, .. })
+    ));
+}
+
+#[test]
+fn label_smoothing_spreads_part_of_each_label() {
+    let y = array![2.0, 0.0];
+    assert_eq!(
+        one_hot_encode(&y, 4).unwrap(),
+        array![[0.0, 0.0, 1.0, 0.0], [1.0, 0.0, 0.0, 0.0]]
+    );
+    let smoothed = one_hot_encode_smoothed(&y, 4, 0.2).unwrap();
+    for (row, label) in smoothed.rows().into_iter().zip([2, 0]) {
+        assert!((row.sum() - 1.0).abs() < 1e-12);
+        for (class, &v) in row.iter().enumerate() {
+            let expected = if class == label { 0.85 } else { 0.05 };
+            assert!((v - expected).abs() < 1e-12);
+        }
+    }
+    assert_eq!(
+        one_hot_encode_smoothed(&y, 4, 1.0).unwrap(),
+        Array1::from_elem(8, 0.25).into_shape((2, 4)).unwrap()
+    );
+    for smoothing in [-0.1, 1.5, f64::NAN] {
+        assert!(matches!(
+            one_hot_encode_smoothed(&y, 4, smoothing),
+            Err(DataError::InvalidSmoothing { .. })
+        ));
+    }
+}
+
+/*Compresses a file into one with .gz added to its name, removing the original*/
+fn gzip(path: &Path) -> PathBuf {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&fs::read(path).unwrap()).unwrap();
+    let mut gz = path.as_os_str().to_owned();
+    gz.push(".gz");
+    let gz = PathBuf::from(gz);
+    fs::write(&gz, encoder.finish().unwrap()).unwrap();
+    fs::remove_file(path).unwrap();
+    gz
+}
+
+#[test]
+fn gzipped_files_are_detected_by_their_contents() {
+    let dir = TempDir::new().unwrap();
+    let plain = dir.path().join("plain.csv");
+    fs::write(&plain, "label,a\n1,2\n").unwrap();
+    let copy = dir.path().join("copy.csv");
+    fs::copy(&plain, &copy).unwrap();
+    //the extension does not matter, only the gzip magic number
+    let compressed = dir.path().join("compressed.csv");
+    fs::rename(gzip(&copy), &compressed).unwrap();
+    let contents = |path: &Path| {
+        let mut text = String::new();
+        open_data(path).unwrap().read_to_string(&mut text).unwrap();
+        text
+    };
+    assert_eq!(contents(&compressed), "label,a\n1,2\n");
+    assert_eq!(contents(&plain), "label,a\n1,2\n");
+}
+
+#[test]
+fn gzipped_idx_files_are_found_next_to_the_plain_names() {
+    let dir = TempDir::new().unwrap();
+    let (images, labels) = ("train-images-idx3-ubyte", "train-labels-idx1-ubyte");
+    idx_file(dir.path(), images, IMAGE_MAGIC, &[1, 1, 2], &[5, 6]);
+    idx_file(dir.path(), labels, LABEL_MAGIC, &[1], &[4]);
+    let expected = array![[4, 5, 6]];
+    let source = DataSource::training(dir.path(), DatasetKind::Mnist);
+    assert_eq!(source.read().unwrap(), expected);
+    //train-images-idx3-ubyte.gz, with the labels left uncompressed
+    gzip(&dir.path().join(images));
+    let source = DataSource::training(dir.path(), DatasetKind::Mnist);
+    assert_eq!(source.read().unwrap(), expected);
+}
@@ -0,0 +1,113 @@
+use neural::networks::schedule::schedule_from_name;
+
+const NAMES: [&str; 8] = [
+    "constant",
+    "step:10:0.5",
+    "exponential:0.9",
+    "cosine:10:2",
+    "warmup:5",
+    "warmup:2+cosine:4:1",
+    "one-cycle",
+    "plateau:2:0.5",
+];
+const BASE: f64 = 0.1;
+const EPOCHS: usize = 20;
+
+/*The learning rate of every epoch, without any losses*/
+fn rates(name: &str) -> Vec<f64> {
+    let schedule = schedule_from_name(name).unwrap();
+    (0..EPOCHS)
+        .map(|epoch| schedule.learn_rate(epoch, EPOCHS, BASE, &[]))
+        .collect()
+}
+
+fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-12, "{} != {}", a, b);
+}
+
+#[test]
+fn schedules_are_chosen_by_name() {
+    for name in NAMES {
+        assert_eq!(schedule_from_name(name).unwrap().name(), name);
+    }
+    assert_eq!(schedule_from_name("step").unwrap().name(), "step:10:0.5");
+    assert_eq!(schedule_from_name("cosine:5").unwrap().name(), "cosine:5:1");
+    assert!(schedule_from_name("constant:1").is_none());
+    assert!(schedule_from_name("step:2.5").is_none());
+    assert!(schedule_from_name("step:10:0.5:1").is_none());
+    assert!(schedule_from_name("cosine+warmup").is_none());
+    assert!(schedule_from_name("linear").is_none());
+    for name in [
+        "exponential:-1",
+        "exponential:0",
+        "exponential:1.5",
+        "step:10:nan",
+        "step:10:-0.5",
+        "plateau:2:inf",
+        "plateau:2:0",
+        "warmup:2+exponential:nan",
+    ] {
+        assert!(schedule_from_name(name).is_none(), "{}", name);
+    }
+    assert_eq!(
+        schedule_from_name("exponential:1").unwrap().name(),
+        "exponential:1"
+    );
+}
+
+#[test]
+fn step_and_exponential_decay() {
+    let step = rates("step:3:0.5");
+    assert_close(step[2], BASE);
+    assert_close(step[3], BASE * 0.5);
+    assert_close(step[7], BASE * 0.25);
+    let exponential = rates("exponential:0.8");
+    assert_close(exponential[0], BASE);
+    assert_close(exponential[2], BASE * 0.64);
+}
+
+#[test]
+fn cosine_restarts_with_longer_periods() {
+    let cosine = rates("cosine:4:2");
+    assert_close(cosine[0], BASE);
+    assert_close(cosine[2], BASE * 0.5);
+    //restarting after 4 epochs, then after 8 more
+    assert_close(cosine[4], BASE);
+    assert_close(cosine[8], BASE * 0.5);
+    assert_close(cosine[12], BASE);
+    assert!(cosine[3] < cosine[2]);
+}
+
+#[test]
+fn warmup_rises_linearly_then_follows_the_next_schedule() {
+    let warmup = rates("warmup:4+step:2:0.5");
+    assert_close(warmup[0], BASE * 0.25);
+    assert_close(warmup[2], BASE * 0.75);
+    assert_close(warmup[4], BASE);
+    assert_close(warmup[6], BASE * 0.5);
+    assert_close(rates("warmup:4")[EPOCHS - 1], BASE);
+}
+
+#[test]
+fn one_cycle_rises_to_the_base_then_anneals() {
+    let cycle = rates("one-cycle");
+    let peak = cycle.iter().cloned().fold(0.0, f64::max);
+    let top = cycle.iter().position(|&r| r == peak).unwrap();
+    assert!((5..=7).contains(&top), "peaked at epoch {}", top);
+    assert!(peak > BASE * 0.95 && peak <= BASE);
+    assert_close(cycle[0], BASE / 25.0);
+    assert!(cycle[EPOCHS - 1] < cycle[0] / 1000.0);
+    assert!(cycle[..=top].windows(2).all(|w| w[0] < w[1]));
+    assert!(cycle[top..].windows(2).all(|w| w[0] > w[1]));
+}
+
+#[test]
+fn plateau_reduces_when_the_loss_stops_improving() {
+    let plateau = schedule_from_name("plateau:2:0.5").unwrap();
+    let rate = |losses: &[f64]| plateau.learn_rate(losses.len(), EPOCHS, BASE, losses);
+    assert_close(rate(&[1.0, 0.8, 0.6]), BASE);
+    assert_close(rate(&[1.0, 0.8, 0.9]), BASE);
+    assert_close(rate(&[1.0, 0.8, 0.9, 0.85]), BASE * 0.5);
+    assert_close(rate(&[1.0, 1.1, 1.2, 1.3, 1.4]), BASE * 0.25);
+    assert_close(rate(&[1.0, 1.1, 1.2, 0.5, 0.6]), BASE * 0.5);
+}
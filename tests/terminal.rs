@@ -0,0 +1,78 @@
+use ndarray::Array1;
+use neural::terminal::{render_activations, render_image, RenderStyle};
+
+const STYLES: [RenderStyle; 3] = [RenderStyle::Ascii, RenderStyle::Blocks, RenderStyle::Color];
+
+/*A square image with a gradient, so every shade appears*/
+fn image(side: usize) -> Array1<f64> {
+    Array1::from_shape_fn(side * side, |i| i as f64 / (side * side - 1) as f64)
+}
+
+/*Removes the ANSI escape sequences from a line, leaving what takes up space on screen*/
+fn visible(line: &str) -> String {
+    let mut text = String::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            text.push(c);
+        }
+    }
+    text
+}
+
+#[test]
+fn styles_are_chosen_by_name() {
+    for (name, style) in ["ascii", "blocks", "color"].into_iter().zip(STYLES) {
+        assert_eq!(RenderStyle::from_name(name), Some(style));
+    }
+    assert_eq!(RenderStyle::from_name("sixel"), None);
+}
+
+#[test]
+fn images_have_the_lines_and_width_of_their_style() {
+    //ascii draws each row twice as wide, the others put two rows on a line
+    for side in [28_usize, 5] {
+        for (style, lines, width) in [
+            (RenderStyle::Ascii, side, 2 * side),
+            (RenderStyle::Blocks, side.div_ceil(2), side),
+            (RenderStyle::Color, side.div_ceil(2), side),
+        ] {
+            let rendered = render_image(&image(side), style);
+            assert_eq!(rendered.len(), lines, "{:?}", style);
+            for line in &rendered {
+                assert_eq!(visible(line).chars().count(), width, "{:?}", style);
+            }
+        }
+    }
+}
+
+#[test]
+fn activations_have_a_cell_per_value() {
+    let values = Array1::from_vec(vec![-2.0, 0.5, 3.0, 1.0, 1.0, -0.5, 0.0]);
+    for style in STYLES {
+        let strip = render_activations(&values, style);
+        assert_eq!(visible(&strip).chars().count(), values.len(), "{:?}", style);
+    }
+    //the lowest value is drawn darkest and the highest brightest
+    let ascii: Vec<char> = render_activations(&values, RenderStyle::Ascii)
+        .chars()
+        .collect();
+    assert_eq!((ascii[0], ascii[2]), (' ', '@'));
+}
+
+#[test]
+fn only_color_uses_escape_sequences() {
+    let values = image(4);
+    for style in STYLES {
+        let mut text = render_image(&image(28), style).concat();
+        text.push_str(&render_activations(&values, style));
+        assert_eq!(
+            text.contains('\x1b'),
+            style == RenderStyle::Color,
+            "{:?}",
+            style
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use std::sync::OnceLock;
+
+use ndarray::s;
+use neural::dataset::Dataset;
+use neural::networks::{
+    activation::{Activation, LeakyRelu, OutputActivation, Sigmoid, Tanh},
+    loss::CrossEntropy,
+    mlp::Mlp,
+    neural_network::{NeuralNetwork, TrainOptions, HIDDEN_SIZE},
+    optimizer::Adam,
+    relu_network::ReluNetwork,
+    schedule::StepDecay,
+    sigmoid_network::SigmoidNetwork,
+    tanh_network::TanhNetwork,
+};
+use neural::synthetic::{synthetic_testing_data, synthetic_training_data, SyntheticDigits};
+
+//small enough to train quickly in a debug build, large enough to learn from
+const TRAIN_SIZE: usize = 200;
+const TEST_SIZE: usize = 50;
+const EPOCHS: i32 = 3;
+//the gradients of a batch are averaged, so the learning rate is larger than for single samples
+const BATCH_SIZE: usize = 10;
+const LEARN_RATE: f64 = 0.5;
+const ADAM_LEARN_RATE: f64 = 0.01;
+//training one sample at a time takes a step per sample, so it uses fewer of them
+const SINGLE_SAMPLES: usize = 100;
+const SINGLE_LEARN_RATE: f64 = 0.05;
+//the initial weights of every network, so that each test trains the same way every time
+const SEED: u64 = 1;
+const INPUTS: usize = 784;
+const CLASSES: usize = 10;
+//how many percentage points training must add to the accuracy of an untrained network
+const MIN_IMPROVEMENT: f64 = 25.0;
+
+/*The training and test images, generated once and shared by every test*/
+fn data() -> &'static (SyntheticDigits, SyntheticDigits) {
+    static DATA: OnceLock<(SyntheticDigits, SyntheticDigits)> = OnceLock::new();
+    DATA.get_or_init(|| {
+        (
+            SyntheticDigits::generate(TRAIN_SIZE, 1),
+            SyntheticDigits::generate(TEST_SIZE, 2),
+        )
+    })
+}
+
+fn batches() -> TrainOptions<'static> {
+    TrainOptions {
+        batch_size: Some(BATCH_SIZE),
+        ..TrainOptions::default()
+    }
+}
+
+/*Trains a network on mini-batches, checking that its accuracy on unseen images
+ * improves and its loss falls*/
+fn assert_training_improves(net: &mut impl NeuralNetwork, learn_rate: f64) {
+    let (x_train, y_train) = data().0.to_arrays();
+    let (x_test, y_test) = data().1.to_arrays();
+    let (before, loss_before) = (net.accuracy(&x_test, &y_test), net.loss(&x_test, &y_test));
+    net.gradient_descent_with(&x_train, &y_train, EPOCHS, learn_rate, &batches());
+    let (after, loss_after) = (net.accuracy(&x_test, &y_test), net.loss(&x_test, &y_test));
+    assert!(
+        after >= before + MIN_IMPROVEMENT,
+        "accuracy went from {:.1}% to {:.1}%",
+        before,
+        after
+    );
+    assert!(
+        loss_after < loss_before,
+        "loss went from {:.4} to {:.4}",
+        loss_before,
+        loss_after
+    );
+}
+
+#[test]
+fn synthetic_data_is_deterministic() {
+    let (x, y) = synthetic_training_data(50, 7);
+    assert_eq!(synthetic_training_data(50, 7), (x.clone(), y));
+    assert_ne!(synthetic_training_data(50, 8).0, x);
+}
+
+#[test]
+fn synthetic_data_has_the_shapes_of_the_mnist_data() {
+    let (x, y) = synthetic_training_data(25, 3);
+    assert_eq!(x.dim(), (25, 784));
+    assert_eq!(y.dim(), (25, 10));
+    assert!(x.iter().all(|v| (0.0..=1.0).contains(v)));
+    assert!(y.rows().into_iter().all(|row| row.sum() == 1.0));
+
+    let (x_test, y_test) = synthetic_testing_data(25, 3);
+    assert_eq!(x_test, x);
+    for (label, one_hot) in y_test.iter().zip(y.rows()) {
+        assert_eq!(one_hot[*label as usize], 1.0);
+    }
+}
+
+#[test]
+fn sigmoid_network_improves_with_training() {
+    let mut net = SigmoidNetwork::with_seed(INPUTS, HIDDEN_SIZE, CLASSES, SEED);
+    assert_training_improves(&mut net, LEARN_RATE);
+}
+
+#[test]
+fn tanh_network_improves_with_training() {
+    let mut net = TanhNetwork::with_seed(INPUTS, HIDDEN_SIZE, CLASSES, SEED);
+    assert_training_improves(&mut net, LEARN_RATE);
+}
+
+#[test]
+fn relu_network_improves_with_training() {
+    let mut net = ReluNetwork::with_seed(INPUTS, HIDDEN_SIZE, CLASSES, SEED);
+    assert_training_improves(&mut net, LEARN_RATE);
+}
+
+#[test]
+fn mlp_with_two_hidden_layers_improves_with_training() {
+    let activations: Vec<Box<dyn Activation>> = vec![Box::new(Sigmoid), Box::new(Sigmoid)];
+    let mut net = Mlp::with_seed(&[INPUTS, 32, 16, CLASSES], activations, SEED);
+    assert_eq!(net.sizes(), &[INPUTS, 32, 16, CLASSES]);
+    assert_training_improves(&mut net, LEARN_RATE);
+    assert_eq!(net.get_hid_layer().dim(), (16, 1));
+}
+
+#[test]
+fn mlp_with_different_activations_per_layer_improves_with_training() {
+    let activations: Vec<Box<dyn Activation>> =
+        vec![Box::new(Tanh), Box::new(LeakyRelu { alpha: 0.01 })];
+    let mut net = Mlp::with_seed(&[INPUTS, 32, 16, CLASSES], activations, SEED);
+    net.set_output_activation(OutputActivation::Softmax);
+    net.set_loss(Box::new(CrossEntropy));
+    assert_training_improves(&mut net, LEARN_RATE);
+}
+
+#[test]
+fn single_sample_training_improves() {
+    let (x_train, y_train) = data().0.to_arrays();
+    let (x_test, y_test) = data().1.to_arrays();
+    let (x_train, y_train) = (
+        x_train.slice_move(s![..SINGLE_SAMPLES, ..]),
+        y_train.slice_move(s![..SINGLE_SAMPLES, ..]),
+    );
+    let mut net = TanhNetwork::with_seed(INPUTS, HIDDEN_SIZE, CLASSES, SEED);
+    let before = net.accuracy(&x_test, &y_test);
+    //without options, gradient_descent updates the weights after every sample
+    net.gradient_descent(&x_train, &y_train, 1, SINGLE_LEARN_RATE);
+    assert!(net.accuracy(&x_test, &y_test) >= before + MIN_IMPROVEMENT);
+}
+
+#[test]
+fn adam_training_improves() {
+    let mut net = TanhNetwork::with_seed(INPUTS, HIDDEN_SIZE, CLASSES, SEED);
+    net.set_optimizer(Box::new(Adam::default()));
+    //adam's steps are about the size of the learning rate, so it needs a small one
+    assert_training_improves(&mut net, ADAM_LEARN_RATE);
+}
+
+#[test]
+fn train_with_learns_from_a_dataset() {
+    let (data, validation) = data();
+    let (x_val, y_val) = validation.to_arrays();
+    let mut net =
+        SigmoidNetwork::with_seed(data.input_dim(), HIDDEN_SIZE, data.num_classes(), SEED);
+    let before = net.accuracy(&x_val, &y_val);
+    let options = TrainOptions {
+        shuffle_seed: Some(6),
+        validation: Some((&x_val, &y_val)),
+        schedule: Some(&StepDecay {
+            step: 2,
+            factor: 0.5,
+        }),
+        ..batches()
+    };
+    net.train_with(data, EPOCHS, LEARN_RATE, &options);
+    assert!(net.accuracy(&x_val, &y_val) >= before + MIN_IMPROVEMENT);
+}